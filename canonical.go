@@ -0,0 +1,77 @@
+package main
+
+import (
+	"fmt"
+	"os"
+	"strings"
+	"unicode"
+)
+
+// canonicalizeSelectors rewrites each selector's XPath to its canonical spelling
+// (see canonicalXpath), noting when different spellings collapse to one form.
+func canonicalizeSelectors(selectors []Selector) []Selector {
+	spellings := make(map[string]string, len(selectors)) // Canonical form -> first spelling
+	canonical := make([]Selector, 0, len(selectors))
+	for _, sel := range selectors {
+		form := canonicalXpath(sel.Xpath)
+		if first, seen := spellings[form]; seen && first != sel.Xpath {
+			fmt.Fprintf(os.Stderr, "Note: XPaths '%s' and '%s' are equivalent. Reporting them as '%s'.\n", first, sel.Xpath, form)
+		} else if !seen {
+			spellings[form] = sel.Xpath
+		}
+		sel.Xpath = form
+		canonical = append(canonical, sel)
+	}
+	return canonical
+}
+
+// canonicalXpath normalizes the spelling of an XPath without changing its meaning:
+// string literals use single quotes unless they contain one, and whitespace outside
+// literals is dropped except for a single space between adjacent words.
+func canonicalXpath(xpathStr string) string {
+	var b strings.Builder
+	runes := []rune(xpathStr)
+	var prev rune // Last rune written outside a literal
+	pendingSpace := false
+	for i := 0; i < len(runes); i++ {
+		r := runes[i]
+		switch {
+		case unicode.IsSpace(r):
+			pendingSpace = true
+			continue
+		case pendingSpace && isXpathWordChar(prev) && isXpathWordChar(r):
+			b.WriteRune(' ')
+		}
+		pendingSpace = false
+
+		if r != '\'' && r != '"' {
+			b.WriteRune(r)
+			prev = r
+			continue
+		}
+
+		end := strings.IndexRune(string(runes[i+1:]), r)
+		if end < 0 {
+			// Unterminated literal: leave the rest as written for Compile to report
+			b.WriteString(string(runes[i:]))
+			break
+		}
+		literal := string(runes[i+1:])[:end]
+		quote := '\''
+		if strings.ContainsRune(literal, '\'') {
+			quote = '"'
+		}
+		b.WriteRune(quote)
+		b.WriteString(literal)
+		b.WriteRune(quote)
+		i += len([]rune(literal)) + 1
+		prev = quote
+	}
+	return b.String()
+}
+
+// isXpathWordChar reports whether r belongs to a name, attribute reference or
+// literal, where a space next to another word character is kept.
+func isXpathWordChar(r rune) bool {
+	return isXpathNameChar(r) || r == '@' || r == '\'' || r == '"'
+}
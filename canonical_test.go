@@ -0,0 +1,47 @@
+package main
+
+import (
+	"encoding/json"
+	"reflect"
+	"testing"
+)
+
+// Test case for collapsing equivalent spellings of an XPath
+func TestProcessInput_CanonicalizeXpaths(t *testing.T) {
+	inputJsonBytes := []byte(`{
+		"xpaths": ["//a[@id=\"x\"]", "//a[@id='x']", "//a[ @id = 'x' ]", "//p[@title=\"it's\"]"],
+		"canonicalize_xpaths": true,
+		"urls": {
+			"http://example.com": {
+				"content": "<html><body><a id=\"x\">Link</a><p title=\"it's\">Quote</p></body></html>"
+			}
+		}
+	}`)
+
+	expectedOutput := OutputJson{
+		// All three spellings are reported under the single canonical key
+		"//a[@id='x']": {
+			"http://example.com": "Link",
+		},
+		// Double quotes are kept for a literal containing a single quote
+		"//p[@title=\"it's\"]": {
+			"http://example.com": "Quote",
+		},
+	}
+
+	actualOutput, err := processInput(inputJsonBytes)
+	if err != nil {
+		t.Fatalf("processInput returned an unexpected error: %v", err)
+	}
+
+	if !reflect.DeepEqual(expectedOutput, actualOutput) {
+		expectedJson, _ := json.MarshalIndent(expectedOutput, "", "  ")
+		actualJson, _ := json.MarshalIndent(actualOutput, "", "  ")
+		t.Errorf("Unexpected output for canonicalized XPaths.\nExpected:\n%s\nGot:\n%s", string(expectedJson), string(actualJson))
+	}
+
+	// Spaces between words are significant and kept
+	if got := canonicalXpath("//a[@id = 'x'  and  @class='y']"); got != "//a[@id='x' and @class='y']" {
+		t.Errorf("Unexpected canonical form: %q", got)
+	}
+}
@@ -0,0 +1,153 @@
+package main
+
+import (
+	"crypto/sha256"
+	"encoding/hex"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"os"
+)
+
+// checkpointInterval is how many newly processed URLs pass between checkpoint saves.
+const checkpointInterval = 50
+
+// checkpoint records which URLs a run has finished and the results so far, so an
+// interrupted run can resume with --checkpoint. URLs are marked completed as they
+// start, which is safe because a run only stops or saves between URLs.
+// A nil *checkpoint records nothing.
+type checkpoint struct {
+	path          string
+	Fingerprint   string          `json:"fingerprint"`
+	CompletedUrls map[string]bool `json:"completed_urls"`
+	Results       OutputJson      `json:"results"`
+	// Why pairs without a value have none (see runResult), so a resumed run reports
+	// the completed URLs as the interrupted one would have
+	UrlErrors  map[string]string            `json:"url_errors"`
+	PairErrors map[string]map[string]string `json:"pair_errors"`
+	Skipped    map[string]map[string]bool   `json:"skipped"`
+	TextUrls   map[string]bool              `json:"text_urls"`
+	// Match counts of the completed URLs, if the run keeps a --match-histogram
+	Histograms matchHistograms `json:"histograms,omitempty"`
+	unsaved    int
+}
+
+// runFingerprint identifies the input and the selectors a run evaluates, so a
+// checkpoint is only resumed by the same run.
+func runFingerprint(input InputJson, selectors []Selector) (string, error) {
+	data, err := json.Marshal([]interface{}{input, selectors})
+	if err != nil {
+		return "", err
+	}
+	sum := sha256.Sum256(data)
+	return hex.EncodeToString(sum[:]), nil
+}
+
+// loadCheckpoint reads the checkpoint at path, or starts a new one if the file does
+// not exist. It fails if the checkpoint was written by a run with a different
+// fingerprint.
+func loadCheckpoint(path, fingerprint string) (*checkpoint, error) {
+	c := &checkpoint{path: path, Fingerprint: fingerprint, CompletedUrls: map[string]bool{}, Results: OutputJson{}}
+	data, err := os.ReadFile(path)
+	if errors.Is(err, os.ErrNotExist) {
+		return c, nil
+	}
+	if err != nil {
+		return nil, fmt.Errorf("reading checkpoint: %w", err)
+	}
+	if err := json.Unmarshal(data, c); err != nil {
+		return nil, fmt.Errorf("parsing checkpoint '%s': %w", path, err)
+	}
+	if c.Fingerprint != fingerprint {
+		return nil, fmt.Errorf("checkpoint '%s' was written for a different input or selectors", path)
+	}
+	return c, nil
+}
+
+// restore copies the checkpointed results and failures into run, for the XPaths
+// it has.
+func (c *checkpoint) restore(run runResult) {
+	if c == nil {
+		return
+	}
+	for xpathStr, results := range c.Results {
+		if _, ok := run.output[xpathStr]; !ok {
+			continue
+		}
+		for url, value := range results {
+			run.output[xpathStr][url] = value
+		}
+	}
+	for url, err := range c.UrlErrors {
+		run.urlErrors[url] = err
+	}
+	for url := range c.TextUrls {
+		run.textUrls[url] = true
+	}
+	for xpathStr, errs := range c.PairErrors {
+		for url, err := range errs {
+			run.pairFailed(xpathStr, url, err)
+		}
+	}
+	for xpathStr, urls := range c.Skipped {
+		for url := range urls {
+			run.skip(xpathStr, url)
+		}
+	}
+	if run.histograms != nil {
+		if c.Histograms == nil && len(c.CompletedUrls) > 0 {
+			fmt.Fprintf(os.Stderr, "Warning: Checkpoint '%s' has no match counts. The match histogram leaves out the %d URLs it completed.\n", c.path, len(c.CompletedUrls))
+		}
+		for xpathStr, counts := range c.Histograms {
+			if histogram := run.histograms[xpathStr]; histogram != nil && counts != nil {
+				for i, n := range counts {
+					histogram[i] += n
+				}
+			}
+		}
+	}
+}
+
+// completed reports whether url was finished by an earlier run.
+func (c *checkpoint) completed(url string) bool {
+	return c != nil && c.CompletedUrls[url]
+}
+
+// start marks url as completed, and saves the results so far once every
+// checkpointInterval URLs.
+func (c *checkpoint) start(url string, run runResult) {
+	if c == nil {
+		return
+	}
+	c.unsaved++
+	if c.unsaved >= checkpointInterval {
+		c.save(run)
+	}
+	c.CompletedUrls[url] = true
+}
+
+// save writes the checkpoint with the results so far, warning if it cannot.
+func (c *checkpoint) save(run runResult) {
+	if c == nil {
+		return
+	}
+	c.Results = run.output
+	c.UrlErrors = run.urlErrors
+	c.PairErrors = run.pairErrors
+	c.Skipped = run.skipped
+	c.TextUrls = run.textUrls
+	c.Histograms = run.histograms
+	c.unsaved = 0
+	data, err := json.Marshal(c)
+	if err == nil {
+		// Write then rename, so an interrupted save leaves the previous checkpoint
+		tmpPath := c.path + ".tmp"
+		err = os.WriteFile(tmpPath, data, 0o644)
+		if err == nil {
+			err = os.Rename(tmpPath, c.path)
+		}
+	}
+	if err != nil {
+		fmt.Fprintf(os.Stderr, "Warning: Failed to save checkpoint '%s': %v\n", c.path, err)
+	}
+}
@@ -0,0 +1,97 @@
+package main
+
+import (
+	"context"
+	"encoding/json"
+	"errors"
+	"path/filepath"
+	"reflect"
+	"strings"
+	"testing"
+
+	"launchpad.net/xmlpath"
+)
+
+// Test case for resuming an interrupted run from a checkpoint
+func TestProcessInput_Checkpoint(t *testing.T) {
+	inputJsonBytes := []byte(`{
+		"xpaths": ["/html/body/p", {"xpath": "//p", "exclude_urls": ["*/1"]}],
+		"urls": {
+			"http://example.com/0": {"content": ""},
+			"http://example.com/1": {"content": "<html><body><p>One</p></body></html>"},
+			"http://example.com/2": {"content": "<html><body><p>Two</p></body></html>"},
+			"http://example.com/3": {"content": "<html><body><p>Three</p></body></html>"}
+		}
+	}`)
+	opts := Options{Checkpoint: filepath.Join(t.TempDir(), "run.checkpoint")}
+
+	// Count evaluations, cancelling the first run once it has started a URL
+	original := evaluateBytes
+	defer func() { evaluateBytes = original }()
+	evaluations := 0
+	var cancel context.CancelCauseFunc
+	evaluateBytes = func(path *xmlpath.Path, root *xmlpath.Node) ([]byte, bool) {
+		evaluations++
+		if cancel != nil {
+			cancel(errInterrupted)
+		}
+		return original(path, root)
+	}
+
+	ctx, cancelFirst := context.WithCancelCause(context.Background())
+	cancel = cancelFirst
+	partialOutput, err := processInputWithOptions(ctx, inputJsonBytes, opts)
+	if !errors.Is(err, errInterrupted) {
+		t.Fatalf("Expected the first run to be interrupted, got %v", err)
+	}
+	if len(partialOutput["/html/body/p"]) != 1 {
+		t.Fatalf("Expected the first run to finish one URL with a value, got %v", partialOutput)
+	}
+
+	// The second run evaluates only the URLs the first did not finish, both selectors on each
+	cancel = nil
+	evaluations = 0
+	input, err := unmarshalInput(inputJsonBytes, opts)
+	if err != nil {
+		t.Fatalf("unmarshalInput returned an unexpected error: %v", err)
+	}
+	run, err := runInput(context.Background(), input, opts)
+	if err != nil {
+		t.Fatalf("runInput returned an unexpected error: %v", err)
+	}
+	if evaluations != 4 {
+		t.Errorf("Expected the resumed run to evaluate 2 URLs, got %d evaluations", evaluations)
+	}
+
+	expectedOutput := OutputJson{
+		"/html/body/p": {
+			"http://example.com/1": "One",
+			"http://example.com/2": "Two",
+			"http://example.com/3": "Three",
+		},
+		"//p": {
+			"http://example.com/2": "Two",
+			"http://example.com/3": "Three",
+		},
+	}
+	actualOutput := run.output
+	if !reflect.DeepEqual(expectedOutput, actualOutput) {
+		expectedJson, _ := json.MarshalIndent(expectedOutput, "", "  ")
+		actualJson, _ := json.MarshalIndent(actualOutput, "", "  ")
+		t.Errorf("Unexpected output for resumed run.\nExpected:\n%s\nGot:\n%s", string(expectedJson), string(actualJson))
+	}
+
+	// The first run's load failure and skipped pair are restored with its results
+	if err := run.pairError("/html/body/p", "http://example.com/0"); !strings.HasPrefix(err, "parsing: ") {
+		t.Errorf("Expected the restored parse error for http://example.com/0, got %q", err)
+	}
+	if !run.isSkipped("//p", "http://example.com/1") {
+		t.Errorf("Expected the restored run to keep //p skipped on http://example.com/1")
+	}
+
+	// A different input does not resume from this checkpoint
+	otherInput := []byte(`{"xpaths": ["//title"], "urls": {"http://example.com/1": {"content": "<html></html>"}}}`)
+	if _, err := processInputWithOptions(context.Background(), otherInput, opts); err == nil {
+		t.Errorf("Expected an error resuming a checkpoint written for another input, got nil")
+	}
+}
@@ -0,0 +1,67 @@
+package main
+
+import (
+	"context"
+	"fmt"
+)
+
+// valueComparison pairs the values one selector extracted for one URL from the
+// primary input and from the --compare-with input. A nil value means no match.
+type valueComparison struct {
+	Value   *string `json:"value"`
+	Other   *string `json:"other"`
+	Differs bool    `json:"differs"`
+}
+
+// ComparisonJson is the --compare-with output: map[xpath]map[url]comparison
+type ComparisonJson map[string]map[string]valueComparison
+
+// compareInputs runs the primary input's selectors over both its own content and
+// the other input's content, and compares the extracted values per selector and URL.
+func compareInputs(ctx context.Context, inputBytes, otherBytes []byte, opts Options) (ComparisonJson, error) {
+	input, err := unmarshalInput(inputBytes, opts)
+	if err != nil {
+		return nil, err
+	}
+	other, err := unmarshalInput(otherBytes, opts)
+	if err != nil {
+		return nil, fmt.Errorf("compare-with input: %w", err)
+	}
+
+	output, err := processInputJson(ctx, input, opts)
+	if err != nil {
+		return nil, err
+	}
+	// Same selectors and settings, other content
+	otherInput := input
+	otherInput.Urls = other.Urls
+	otherOutput, err := processInputJson(ctx, otherInput, opts)
+	if err != nil {
+		return nil, err
+	}
+
+	urls := make(map[string]bool, len(input.Urls)+len(other.Urls))
+	for url := range input.Urls {
+		urls[url] = true
+	}
+	for url := range other.Urls {
+		urls[url] = true
+	}
+
+	comparison := make(ComparisonJson, len(output))
+	for xpathStr, values := range output {
+		comparison[xpathStr] = make(map[string]valueComparison, len(urls))
+		for url := range urls {
+			var c valueComparison
+			if value, ok := values[url]; ok {
+				c.Value = &value
+			}
+			if value, ok := otherOutput[xpathStr][url]; ok {
+				c.Other = &value
+			}
+			c.Differs = (c.Value == nil) != (c.Other == nil) || (c.Value != nil && *c.Value != *c.Other)
+			comparison[xpathStr][url] = c
+		}
+	}
+	return comparison, nil
+}
@@ -0,0 +1,41 @@
+package main
+
+import (
+	"context"
+	"testing"
+)
+
+// Test case for comparing extracted values between a golden and a live input
+func TestCompareInputs(t *testing.T) {
+	golden := []byte(`{
+		"xpaths": ["//title", "/html/body/p"],
+		"urls": {
+			"http://example.com": {"content": "<html><head><title>Shop</title></head><body><p>In stock</p></body></html>"}
+		}
+	}`)
+	// The live input's own selectors are ignored; only its content is used
+	live := []byte(`{
+		"xpaths": [],
+		"urls": {
+			"http://example.com": {"content": "<html><head><title>Shop</title></head><body><p>Sold out</p></body></html>"}
+		}
+	}`)
+
+	comparison, err := compareInputs(context.Background(), golden, live, Options{})
+	if err != nil {
+		t.Fatalf("compareInputs returned an unexpected error: %v", err)
+	}
+
+	title := comparison["//title"]["http://example.com"]
+	if title.Differs || title.Value == nil || *title.Value != "Shop" {
+		t.Errorf("Expected an unchanged title, got %+v", title)
+	}
+
+	p := comparison["/html/body/p"]["http://example.com"]
+	if !p.Differs {
+		t.Errorf("Expected the changed paragraph to be flagged, got %+v", p)
+	}
+	if p.Value == nil || *p.Value != "In stock" || p.Other == nil || *p.Other != "Sold out" {
+		t.Errorf("Expected both values to be reported, got %+v", p)
+	}
+}
@@ -0,0 +1,81 @@
+package main
+
+// runConfig is the effective configuration of a run, combining command-line options
+// with settings read from the input, as printed by --dump-config.
+type runConfig struct {
+	// From the command line
+	RendererURL       string   `json:"renderer_url"`
+	ContentDir        string   `json:"content_dir"`
+	Checkpoint        string   `json:"checkpoint"`
+	LenientUrls       bool     `json:"lenient_urls"`
+	Tags              []string `json:"tags"`
+	TagMode           string   `json:"tag_mode"`
+	IncludeUntagged   bool     `json:"include_untagged"`
+	TimingReport      bool     `json:"timing_report"`
+	MemoryReport      bool     `json:"memory_report"`
+	Format            string   `json:"format"`
+	Compact           bool     `json:"compact"`
+	WithProvenance    bool     `json:"with_provenance"`
+	WithRunMeta       bool     `json:"with_run_meta"`
+	MaxRuntimeSeconds float64  `json:"max_runtime_seconds"`
+
+	// From the input
+	NormalizeTagCase   bool     `json:"normalize_tag_case"`
+	AllowedTags        []string `json:"allowed_tags"`
+	OnDuplicateUrl     string   `json:"on_duplicate_url"`
+	EvalTimeoutMs      int      `json:"eval_timeout_ms"`
+	InvalidEncoding    string   `json:"invalid_encoding"`
+	CanonicalizeXpaths bool     `json:"canonicalize_xpaths"`
+	Selectors          int      `json:"selectors"`
+	Urls               int      `json:"urls"`
+}
+
+// resolveConfig decodes the input as a run would and returns the settings that
+// would take effect, with defaults filled in.
+func resolveConfig(inputBytes []byte, opts Options) (runConfig, error) {
+	input, err := unmarshalInput(inputBytes, opts)
+	if err != nil {
+		return runConfig{}, err
+	}
+
+	onDuplicateUrl := input.OnDuplicateUrl
+	if onDuplicateUrl == "" {
+		onDuplicateUrl = "last" // Unchecked: the last entry silently wins
+	}
+
+	invalidEncoding := input.InvalidEncoding
+	if invalidEncoding == "" {
+		invalidEncoding = invalidEncodingError
+	}
+
+	selectors := filterByTags(input.Xpaths, opts)
+	if input.CanonicalizeXpaths {
+		selectors = canonicalizeSelectors(selectors)
+	}
+
+	return runConfig{
+		RendererURL:       opts.RendererURL,
+		ContentDir:        opts.ContentDir,
+		Checkpoint:        opts.Checkpoint,
+		LenientUrls:       opts.LenientUrls,
+		Tags:              opts.Tags,
+		TagMode:           opts.TagMode,
+		IncludeUntagged:   opts.IncludeUntagged,
+		TimingReport:      opts.TimingReport != nil,
+		MemoryReport:      opts.MemoryReport != nil,
+		Format:            opts.Format,
+		Compact:           opts.Compact,
+		WithProvenance:    opts.WithProvenance,
+		WithRunMeta:       opts.WithRunMeta,
+		MaxRuntimeSeconds: opts.MaxRuntime.Seconds(),
+
+		NormalizeTagCase:   input.NormalizeTagCase,
+		AllowedTags:        input.AllowedTags,
+		OnDuplicateUrl:     onDuplicateUrl,
+		EvalTimeoutMs:      input.EvalTimeoutMs,
+		InvalidEncoding:    invalidEncoding,
+		CanonicalizeXpaths: input.CanonicalizeXpaths,
+		Selectors:          len(dedupXpaths(selectors)),
+		Urls:               len(input.Urls),
+	}, nil
+}
@@ -0,0 +1,43 @@
+package main
+
+import (
+	"encoding/json"
+	"reflect"
+	"testing"
+	"time"
+)
+
+// Test case for resolving command-line options and input settings together
+func TestResolveConfig(t *testing.T) {
+	inputJsonBytes := []byte(`{
+		"xpaths": ["/html/body/p", "//title", "/html/body/p"],
+		"normalize_tag_case": true,
+		"urls": {
+			"http://example.com": {"content": "<p>Hello</p>"}
+		}
+	}`)
+
+	opts := Options{ContentDir: "fixtures", Compact: true, MaxRuntime: 90 * time.Second}
+	config, err := resolveConfig(inputJsonBytes, opts)
+	if err != nil {
+		t.Fatalf("resolveConfig returned an unexpected error: %v", err)
+	}
+
+	expectedConfig := runConfig{
+		ContentDir:        "fixtures",
+		Compact:           true,
+		MaxRuntimeSeconds: 90,
+		NormalizeTagCase:  true,
+		// Defaults are shown explicitly
+		OnDuplicateUrl:  "last",
+		InvalidEncoding: "error",
+		// Duplicate selectors are counted once, as they are evaluated once
+		Selectors: 2,
+		Urls:      1,
+	}
+	if !reflect.DeepEqual(expectedConfig, config) {
+		expectedJson, _ := json.MarshalIndent(expectedConfig, "", "  ")
+		actualJson, _ := json.MarshalIndent(config, "", "  ")
+		t.Errorf("Unexpected resolved config.\nExpected:\n%s\nGot:\n%s", string(expectedJson), string(actualJson))
+	}
+}
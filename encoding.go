@@ -0,0 +1,40 @@
+package main
+
+import (
+	"fmt"
+	"os"
+	"regexp"
+	"strings"
+	"unicode/utf8"
+)
+
+// Policies for content that is not valid UTF-8.
+const (
+	invalidEncodingError   = "error"   // Skip the URL with an "invalid encoding" error
+	invalidEncodingReplace = "replace" // Replace invalid bytes with U+FFFD and continue
+)
+
+// xmlEncodingDecl matches the encoding named by a leading XML declaration.
+var xmlEncodingDecl = regexp.MustCompile(`^\s*<\?xml[^>]*\sencoding\s*=\s*["']([^"']+)["']`)
+
+// checkEncoding applies the invalid_encoding policy to content that is not valid
+// UTF-8. Content whose XML declaration names another encoding is left for the
+// decoder's charset conversion.
+func checkEncoding(url, content, policy string) (string, error) {
+	if utf8.ValidString(content) {
+		return content, nil
+	}
+	if m := xmlEncodingDecl.FindStringSubmatch(content); m != nil && !strings.EqualFold(m[1], "utf-8") {
+		return content, nil
+	}
+
+	switch policy {
+	case "", invalidEncodingError:
+		return "", fmt.Errorf("invalid encoding: content is not valid UTF-8")
+	case invalidEncodingReplace:
+		fmt.Fprintf(os.Stderr, "Warning: Content for URL '%s' is not valid UTF-8. Replacing invalid bytes.\n", url)
+		return strings.ToValidUTF8(content, "\uFFFD"), nil
+	default:
+		return "", fmt.Errorf("invalid invalid_encoding %q: expected %q or %q", policy, invalidEncodingError, invalidEncodingReplace)
+	}
+}
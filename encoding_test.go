@@ -0,0 +1,43 @@
+package main
+
+import (
+	"context"
+	"fmt"
+	"os"
+	"testing"
+)
+
+// Test case for content bytes that are not valid UTF-8
+func TestProcessInput_InvalidEncoding(t *testing.T) {
+	dir := t.TempDir()
+	fixture := []byte("<html><body><p>Caf\xe9</p></body></html>") // Latin-1 e-acute, undeclared
+	if err := os.WriteFile(fixturePath(dir, "http://latin1.com"), fixture, 0o644); err != nil {
+		t.Fatalf("Failed to write fixture: %v", err)
+	}
+
+	inputFor := func(policy string) []byte {
+		return []byte(fmt.Sprintf(`{
+			"xpaths": ["/html/body/p"],
+			"invalid_encoding": "%s",
+			"urls": {"http://latin1.com": {}}
+		}`, policy))
+	}
+
+	// The default policy skips the URL with an invalid encoding error
+	actualOutput, err := processInputWithOptions(context.Background(), inputFor(""), Options{ContentDir: dir})
+	if err != nil {
+		t.Fatalf("processInputWithOptions returned an unexpected error: %v", err)
+	}
+	if _, ok := actualOutput["/html/body/p"]["http://latin1.com"]; ok {
+		t.Errorf("Expected invalid UTF-8 content to be skipped, got %v", actualOutput)
+	}
+
+	// The replace policy decodes with U+FFFD in place of the bad byte
+	actualOutput, err = processInputWithOptions(context.Background(), inputFor("replace"), Options{ContentDir: dir})
+	if err != nil {
+		t.Fatalf("processInputWithOptions returned an unexpected error: %v", err)
+	}
+	if got := actualOutput["/html/body/p"]["http://latin1.com"]; got != "Caf\uFFFD" {
+		t.Errorf("Expected replaced content \"Caf\\uFFFD\", got %q", got)
+	}
+}
@@ -0,0 +1,37 @@
+package main
+
+import (
+	"regexp"
+	"strings"
+)
+
+// globRegexp compiles an exclude_urls glob, in which * matches any run of
+// characters including '/' and ? matches any single character.
+func globRegexp(glob string) *regexp.Regexp {
+	quoted := regexp.QuoteMeta(glob)
+	quoted = strings.ReplaceAll(quoted, `\*`, ".*")
+	quoted = strings.ReplaceAll(quoted, `\?`, ".")
+	return regexp.MustCompile("^" + quoted + "$")
+}
+
+// compileExcludeUrls compiles the exclude_urls globs of each selector that has any,
+// keyed by XPath.
+func compileExcludeUrls(selectors []Selector) map[string][]*regexp.Regexp {
+	excludeUrls := make(map[string][]*regexp.Regexp)
+	for _, sel := range selectors {
+		for _, glob := range sel.ExcludeUrls {
+			excludeUrls[sel.Xpath] = append(excludeUrls[sel.Xpath], globRegexp(glob))
+		}
+	}
+	return excludeUrls
+}
+
+// excludedUrl reports whether url matches any of the globs.
+func excludedUrl(globs []*regexp.Regexp, url string) bool {
+	for _, glob := range globs {
+		if glob.MatchString(url) {
+			return true
+		}
+	}
+	return false
+}
@@ -0,0 +1,47 @@
+package main
+
+import (
+	"encoding/json"
+	"reflect"
+	"testing"
+)
+
+// Test case for keeping a selector off URLs matching exclude_urls
+func TestProcessInput_ExcludeUrls(t *testing.T) {
+	inputJsonBytes := []byte(`{
+		"xpaths": [
+			{"xpath": "//div[@id='banner']", "exclude_urls": ["*/admin/*"]},
+			"//title"
+		],
+		"urls": {
+			"http://example.com/home": {
+				"content": "<html><head><title>Home</title></head><body><div id=\"banner\">Sale</div></body></html>"
+			},
+			"http://example.com/admin/users": {
+				"content": "<html><head><title>Users</title></head><body><div id=\"banner\">Sale</div></body></html>"
+			}
+		}
+	}`)
+
+	// The glob's * spans the scheme and host; other selectors still see admin URLs
+	expectedOutput := OutputJson{
+		"//div[@id='banner']": {
+			"http://example.com/home": "Sale",
+		},
+		"//title": {
+			"http://example.com/home":        "Home",
+			"http://example.com/admin/users": "Users",
+		},
+	}
+
+	actualOutput, err := processInput(inputJsonBytes)
+	if err != nil {
+		t.Fatalf("processInput returned an unexpected error: %v", err)
+	}
+
+	if !reflect.DeepEqual(expectedOutput, actualOutput) {
+		expectedJson, _ := json.MarshalIndent(expectedOutput, "", "  ")
+		actualJson, _ := json.MarshalIndent(actualOutput, "", "  ")
+		t.Errorf("Unexpected output for excluded URLs.\nExpected:\n%s\nGot:\n%s", string(expectedJson), string(actualJson))
+	}
+}
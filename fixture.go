@@ -0,0 +1,39 @@
+package main
+
+import (
+	"fmt"
+	"strings"
+)
+
+// generateFixture builds a deterministic benchmark input: urlCount pages, each with
+// domSize items, and selectorCount distinct selectors that each match one item per
+// page. Beyond domSize selectors, items are selected again through extra "/../span"
+// steps so every selector stays distinct.
+func generateFixture(urlCount, selectorCount, domSize int) InputJson {
+	if domSize < 1 {
+		domSize = 1
+	}
+
+	input := InputJson{
+		Xpaths: make([]Selector, 0, selectorCount),
+		Urls:   make(map[string]UrlData, urlCount),
+	}
+	for s := 0; s < selectorCount; s++ {
+		input.Xpaths = append(input.Xpaths, Selector{
+			Xpath: fmt.Sprintf("//div[@id='item-%d']/span", s%domSize) + strings.Repeat("/../span", s/domSize),
+		})
+	}
+
+	for u := 0; u < urlCount; u++ {
+		var content strings.Builder
+		content.WriteString("<html><head><title>Fixture page ")
+		fmt.Fprintf(&content, "%d</title></head><body>", u)
+		for i := 0; i < domSize; i++ {
+			fmt.Fprintf(&content, `<div id="item-%d" class="item"><span>Page %d item %d</span></div>`, i, u, i)
+		}
+		content.WriteString("</body></html>")
+
+		input.Urls[fmt.Sprintf("http://fixture.example/page/%d", u)] = UrlData{Content: content.String()}
+	}
+	return input
+}
@@ -0,0 +1,68 @@
+package main
+
+import (
+	"encoding/json"
+	"testing"
+)
+
+// Test case for the generated benchmark fixture
+func TestGenerateFixture(t *testing.T) {
+	fixtureJson, err := json.Marshal(generateFixture(3, 4, 10))
+	if err != nil {
+		t.Fatalf("Failed to marshal fixture: %v", err)
+	}
+
+	// The fixture must round-trip through the normal input path and run
+	actualOutput, err := processInput(fixtureJson)
+	if err != nil {
+		t.Fatalf("processInput returned an unexpected error: %v", err)
+	}
+
+	if len(actualOutput) != 4 {
+		t.Errorf("Expected 4 selectors in output, got %d", len(actualOutput))
+	}
+	for xpathStr, results := range actualOutput {
+		if len(results) != 3 {
+			t.Errorf("Expected selector %s to match all 3 URLs, got %d", xpathStr, len(results))
+		}
+	}
+	if got := actualOutput["//div[@id='item-2']/span"]["http://fixture.example/page/1"]; got != "Page 1 item 2" {
+		t.Errorf("Unexpected value for item 2 on page 1: %q", got)
+	}
+
+	// Generation is deterministic
+	again, _ := json.Marshal(generateFixture(3, 4, 10))
+	if string(again) != string(fixtureJson) {
+		t.Errorf("Expected identical fixtures for identical parameters")
+	}
+}
+
+// Test case for a fixture with more selectors than items per page
+func TestGenerateFixture_MoreSelectorsThanItems(t *testing.T) {
+	fixture := generateFixture(2, 5, 2)
+	if unique := dedupXpaths(fixture.Xpaths); len(unique) != 5 {
+		t.Fatalf("Expected 5 distinct selectors, got %d: %v", len(unique), fixture.Xpaths)
+	}
+
+	fixtureJson, err := json.Marshal(fixture)
+	if err != nil {
+		t.Fatalf("Failed to marshal fixture: %v", err)
+	}
+	actualOutput, err := processInput(fixtureJson)
+	if err != nil {
+		t.Fatalf("processInput returned an unexpected error: %v", err)
+	}
+
+	// Every selector still matches its item on every page
+	if len(actualOutput) != 5 {
+		t.Errorf("Expected 5 selectors in output, got %d", len(actualOutput))
+	}
+	for xpathStr, results := range actualOutput {
+		if len(results) != 2 {
+			t.Errorf("Expected selector %s to match both URLs, got %d", xpathStr, len(results))
+		}
+	}
+	if got := actualOutput["//div[@id='item-0']/span/../span/../span"]["http://fixture.example/page/1"]; got != "Page 1 item 0" {
+		t.Errorf("Unexpected value for the third selector of item 0 on page 1: %q", got)
+	}
+}
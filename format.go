@@ -0,0 +1,205 @@
+package main
+
+import (
+	"fmt"
+	"os"
+	"sort"
+	"strings"
+)
+
+// Output formats selected with --format.
+const (
+	formatJson    = "json"        // OutputJson: map[xpath]map[url]value
+	formatRecords = "records"     // Flat array of record, one per evaluated (selector, URL) pair
+	formatByUrl   = "by-url"      // Map of URL to urlSummary
+	formatTap     = "tap"         // Test Anything Protocol text; see toTap
+	formatPretty  = "pretty-term" // Report for reading in a terminal; see toPrettyTerm
+)
+
+// validateFormat rejects unknown --format values before any work is done.
+func validateFormat(format string) error {
+	switch format {
+	case formatJson, formatRecords, formatByUrl, formatTap, formatPretty:
+		return nil
+	default:
+		return fmt.Errorf("unknown output format %q", format)
+	}
+}
+
+// shapeOutput converts a run's results into the value to serialize for a JSON format.
+func shapeOutput(run runResult, format string) (interface{}, error) {
+	switch format {
+	case formatJson:
+		return run.output, nil
+	case formatRecords:
+		return toRecords(run), nil
+	case formatByUrl:
+		return byUrl(run), nil
+	default:
+		return nil, fmt.Errorf("unknown output format %q", format)
+	}
+}
+
+// record is one evaluated (selector, URL) pair in the flat "records" format.
+// Value, serialized as "actual", is empty unless Matched; Error says why an unmatched
+// pair failed, if it did.
+type record struct {
+	Selector string `json:"selector"`
+	Url      string `json:"url"`
+	Value    string `json:"actual"`
+	Matched  bool   `json:"matched"`
+	Error    string `json:"error,omitempty"`
+}
+
+// sortedXpaths returns the run's selectors in sorted order.
+func sortedXpaths(run runResult) []string {
+	xpaths := make([]string, 0, len(run.output))
+	for xpathStr := range run.output {
+		xpaths = append(xpaths, xpathStr)
+	}
+	sort.Strings(xpaths)
+	return xpaths
+}
+
+// toRecords lists every evaluated pair, sorted by selector, then URL. Pairs the
+// run skipped (url_policy, exclude_urls) are left out.
+func toRecords(run runResult) []record {
+	records := []record{} // Marshals as [] rather than null when empty
+	for _, xpathStr := range sortedXpaths(run) {
+		for _, url := range run.urls {
+			if run.isSkipped(xpathStr, url) {
+				continue
+			}
+			value, ok := run.output[xpathStr][url]
+			r := record{Selector: xpathStr, Url: url, Value: value, Matched: ok}
+			if !ok {
+				r.Error = run.pairError(xpathStr, url)
+			}
+			records = append(records, r)
+		}
+	}
+	return records
+}
+
+// urlSummary lists, for one URL in the "by-url" format, the selectors that
+// produced a value there and those that did not.
+type urlSummary struct {
+	Successful   []string `json:"successful"`
+	Unsuccessful []string `json:"unsuccessful"`
+}
+
+// byUrl summarizes each URL the run reached, including URLs that matched
+// nothing or failed to load, with selectors sorted. Skipped pairs are in neither list.
+func byUrl(run runResult) map[string]*urlSummary {
+	xpaths := sortedXpaths(run)
+	summaries := make(map[string]*urlSummary, len(run.urls))
+	for _, url := range run.urls {
+		summary := &urlSummary{Successful: []string{}, Unsuccessful: []string{}}
+		for _, xpathStr := range xpaths {
+			if run.isSkipped(xpathStr, url) {
+				continue
+			}
+			if _, ok := run.output[xpathStr][url]; ok {
+				summary.Successful = append(summary.Successful, xpathStr)
+			} else {
+				summary.Unsuccessful = append(summary.Unsuccessful, xpathStr)
+			}
+		}
+		summaries[url] = summary
+	}
+	return summaries
+}
+
+// toTap renders the results as TAP, with one test per (selector, URL) pair that
+// passes if the selector produced a value there. Pairs are ordered by selector,
+// then URL, over every URL the run reached; skipped pairs are marked SKIP.
+func toTap(run runResult) string {
+	var b strings.Builder
+	b.WriteString("TAP version 13\n")
+	n := 0
+	for _, xpathStr := range sortedXpaths(run) {
+		for _, url := range run.urls {
+			n++
+			if run.isSkipped(xpathStr, url) {
+				fmt.Fprintf(&b, "ok %d - %s %s # SKIP not evaluated\n", n, xpathStr, url)
+				continue
+			}
+			if _, ok := run.output[xpathStr][url]; ok {
+				fmt.Fprintf(&b, "ok %d - %s %s\n", n, xpathStr, url)
+				continue
+			}
+			message := run.pairError(xpathStr, url)
+			if message == "" {
+				message = "no value extracted"
+			}
+			fmt.Fprintf(&b, "not ok %d - %s %s\n", n, xpathStr, url)
+			fmt.Fprintf(&b, "  ---\n  message: %q\n  selector: %q\n  url: %q\n  ...\n", message, xpathStr, url)
+		}
+	}
+	fmt.Fprintf(&b, "1..%d\n", n)
+	return b.String()
+}
+
+// ANSI escapes used by toPrettyTerm.
+const (
+	ansiGreen = "\x1b[32m"
+	ansiRed   = "\x1b[31m"
+	ansiReset = "\x1b[0m"
+)
+
+// toPrettyTerm renders a report for reading in a terminal: each selector, sorted,
+// with its tallies aligned, followed by the URLs it produced a value on (+, green)
+// and those it did not (-, red, with the error if any). Colors are only used if
+// color is set. Skipped pairs are left out.
+func toPrettyTerm(run runResult, color bool) string {
+	paint := func(code, text string) string {
+		if !color {
+			return text
+		}
+		return code + text + ansiReset
+	}
+
+	xpaths := sortedXpaths(run)
+	width := 0
+	for _, xpathStr := range xpaths {
+		if len(xpathStr) > width {
+			width = len(xpathStr)
+		}
+	}
+
+	var b strings.Builder
+	for _, xpathStr := range xpaths {
+		var lines []string
+		successful, unsuccessful := 0, 0
+		for _, url := range run.urls {
+			if run.isSkipped(xpathStr, url) {
+				continue
+			}
+			if _, ok := run.output[xpathStr][url]; ok {
+				successful++
+				lines = append(lines, paint(ansiGreen, "  + "+url))
+				continue
+			}
+			unsuccessful++
+			line := "  - " + url
+			if err := run.pairError(xpathStr, url); err != "" {
+				line += " (" + err + ")"
+			}
+			lines = append(lines, paint(ansiRed, line))
+		}
+		fmt.Fprintf(&b, "%-*s  %s  %s\n", width, xpathStr,
+			paint(ansiGreen, fmt.Sprintf("%d successful", successful)),
+			paint(ansiRed, fmt.Sprintf("%d unsuccessful", unsuccessful)))
+		for _, line := range lines {
+			b.WriteString(line + "\n")
+		}
+	}
+	return b.String()
+}
+
+// stdoutIsTerminal reports whether stdout is a terminal, so that color is only
+// used when a person is likely reading it.
+func stdoutIsTerminal() bool {
+	info, err := os.Stdout.Stat()
+	return err == nil && info.Mode()&os.ModeCharDevice != 0
+}
@@ -0,0 +1,173 @@
+package main
+
+import (
+	"context"
+	"encoding/json"
+	"reflect"
+	"strings"
+	"testing"
+)
+
+// formatTestInput has a URL every selector misses and one that fails to parse,
+// besides URLs with matches.
+var formatTestInput = []byte(`{
+	"xpaths": ["/html/body/p", "//title", "//nonexistent"],
+	"urls": {
+		"http://example.com": {
+			"content": "<html><head><title>Test Page</title></head><body><p>Hello</p></body></html>"
+		},
+		"http://other.com": {
+			"content": "<html><body><p>World</p></body></html>"
+		},
+		"http://nothing.com": {
+			"content": "<html><body><div>Nothing here</div></body></html>"
+		},
+		"http://broken.com": {
+			"content": ""
+		}
+	}
+}`)
+
+// formatTestRun processes formatTestInput for the format tests.
+func formatTestRun(t *testing.T) runResult {
+	t.Helper()
+	input, err := unmarshalInput(formatTestInput, Options{})
+	if err != nil {
+		t.Fatalf("unmarshalInput returned an unexpected error: %v", err)
+	}
+	var run runResult
+	captureStderr(t, func() { // Silence the warning for http://broken.com
+		run, err = runInput(context.Background(), input, Options{})
+	})
+	if err != nil {
+		t.Fatalf("runInput returned an unexpected error: %v", err)
+	}
+	return run
+}
+
+// Test case for the flat records output format
+func TestShapeOutput_Records(t *testing.T) {
+	shaped, err := shapeOutput(formatTestRun(t), formatRecords)
+	if err != nil {
+		t.Fatalf("shapeOutput returned an unexpected error: %v", err)
+	}
+	recordsJson, err := marshalOutput(shaped, true)
+	if err != nil {
+		t.Fatalf("marshalOutput returned an unexpected error: %v", err)
+	}
+
+	var actualRecords []record
+	if err := json.Unmarshal(recordsJson, &actualRecords); err != nil {
+		t.Fatalf("Failed to parse records output: %v", err)
+	}
+
+	// One record per evaluated pair; the load failure is reported on each of its records
+	for i, r := range actualRecords {
+		if r.Url == "http://broken.com" {
+			if !strings.HasPrefix(r.Error, "parsing: ") {
+				t.Errorf("Expected a parse error on %+v", r)
+			}
+			actualRecords[i].Error = ""
+		}
+	}
+	expectedRecords := []record{
+		{Selector: "//nonexistent", Url: "http://broken.com"},
+		{Selector: "//nonexistent", Url: "http://example.com"},
+		{Selector: "//nonexistent", Url: "http://nothing.com"},
+		{Selector: "//nonexistent", Url: "http://other.com"},
+		{Selector: "//title", Url: "http://broken.com"},
+		{Selector: "//title", Url: "http://example.com", Value: "Test Page", Matched: true},
+		{Selector: "//title", Url: "http://nothing.com"},
+		{Selector: "//title", Url: "http://other.com"},
+		{Selector: "/html/body/p", Url: "http://broken.com"},
+		{Selector: "/html/body/p", Url: "http://example.com", Value: "Hello", Matched: true},
+		{Selector: "/html/body/p", Url: "http://nothing.com"},
+		{Selector: "/html/body/p", Url: "http://other.com", Value: "World", Matched: true},
+	}
+	if !reflect.DeepEqual(expectedRecords, actualRecords) {
+		t.Errorf("Unexpected records.\nExpected: %+v\nGot: %+v", expectedRecords, actualRecords)
+	}
+}
+
+// Test case for the URL-centric by-url output format
+func TestShapeOutput_ByUrl(t *testing.T) {
+	shaped, err := shapeOutput(formatTestRun(t), formatByUrl)
+	if err != nil {
+		t.Fatalf("shapeOutput returned an unexpected error: %v", err)
+	}
+
+	// Every URL is summarized, including those that matched nothing or failed to load
+	expected := map[string]*urlSummary{
+		"http://broken.com": {
+			Successful:   []string{},
+			Unsuccessful: []string{"//nonexistent", "//title", "/html/body/p"},
+		},
+		"http://example.com": {
+			Successful:   []string{"//title", "/html/body/p"},
+			Unsuccessful: []string{"//nonexistent"},
+		},
+		"http://nothing.com": {
+			Successful:   []string{},
+			Unsuccessful: []string{"//nonexistent", "//title", "/html/body/p"},
+		},
+		"http://other.com": {
+			Successful:   []string{"/html/body/p"},
+			Unsuccessful: []string{"//nonexistent", "//title"},
+		},
+	}
+	if !reflect.DeepEqual(expected, shaped) {
+		expectedJson, _ := json.MarshalIndent(expected, "", "  ")
+		actualJson, _ := json.MarshalIndent(shaped, "", "  ")
+		t.Errorf("Unexpected by-url output.\nExpected:\n%s\nGot:\n%s", string(expectedJson), string(actualJson))
+	}
+}
+
+// Test case for TAP output with one test per (selector, URL) pair, including
+// URLs that matched nothing or failed to load
+func TestToTap(t *testing.T) {
+	tap := toTap(formatTestRun(t))
+	lines := strings.Split(strings.TrimSuffix(tap, "\n"), "\n")
+	okCount, notOkCount := 0, 0
+	for _, line := range lines {
+		switch {
+		case strings.HasPrefix(line, "ok "):
+			okCount++
+		case strings.HasPrefix(line, "not ok "):
+			notOkCount++
+		}
+	}
+
+	// 3 selectors x 4 URLs, of which only //title once and /html/body/p twice passed
+	if okCount != 3 || notOkCount != 9 {
+		t.Errorf("Expected 3 ok and 9 not ok lines, got %d and %d:\n%s", okCount, notOkCount, tap)
+	}
+	if plan := lines[len(lines)-1]; plan != "1..12" {
+		t.Errorf("Expected the plan line 1..12 last, got %q", plan)
+	}
+	if !strings.Contains(tap, "not ok 11 - /html/body/p http://nothing.com\n  ---\n  message: \"no value extracted\"\n") {
+		t.Errorf("Expected /html/body/p to fail on http://nothing.com as test 11, got:\n%s", tap)
+	}
+	if !strings.Contains(tap, "not ok 9 - /html/body/p http://broken.com\n  ---\n  message: \"parsing: ") {
+		t.Errorf("Expected /html/body/p on http://broken.com to fail with the parse error as test 9, got:\n%s", tap)
+	}
+}
+
+// Test case for the terminal report with color forced off
+func TestToPrettyTerm(t *testing.T) {
+	report := toPrettyTerm(formatTestRun(t), false)
+
+	// Each selector with its tallies, columns aligned, then its URLs
+	for _, want := range []string{
+		"//nonexistent  0 successful  4 unsuccessful\n",
+		"//title        1 successful  3 unsuccessful\n  - http://broken.com (parsing: ",
+		"/html/body/p   2 successful  2 unsuccessful\n",
+		"  + http://example.com\n  - http://nothing.com\n  + http://other.com\n",
+	} {
+		if !strings.Contains(report, want) {
+			t.Errorf("Expected the report to contain %q, got:\n%s", want, report)
+		}
+	}
+	if strings.Contains(report, "\x1b[") {
+		t.Errorf("Expected no color escapes with color off, got:\n%q", report)
+	}
+}
@@ -0,0 +1,24 @@
+package main
+
+import "fmt"
+
+// Values for UrlData.ParseMode.
+const (
+	parseModeDocument = "document" // The default: content is a complete document
+	parseModeFragment = "fragment" // Content is wrapped in <html><body> before parsing
+)
+
+// applyParseMode prepares content for parsing according to mode. A fragment is
+// wrapped in <html><body>...</body></html>, so it may have several top-level
+// nodes and absolute XPaths such as /html/body/p address it the same way they
+// would a full page.
+func applyParseMode(content, mode string) (string, error) {
+	switch mode {
+	case "", parseModeDocument:
+		return content, nil
+	case parseModeFragment:
+		return "<html><body>" + content + "</body></html>", nil
+	default:
+		return "", fmt.Errorf("invalid parse_mode %q: expected %q or %q", mode, parseModeDocument, parseModeFragment)
+	}
+}
@@ -0,0 +1,46 @@
+package main
+
+import (
+	"encoding/json"
+	"reflect"
+	"testing"
+)
+
+// Test case for parsing content as a fragment
+func TestProcessInput_FragmentParseMode(t *testing.T) {
+	inputJsonBytes := []byte(`{
+		"xpaths": ["//p/text()", "/html/body/p[2]"],
+		"urls": {
+			"http://example.com/fragment": {
+				"parse_mode": "fragment",
+				"content": "<p>Hello</p><p>World</p>"
+			},
+			"http://example.com/page": {
+				"content": "<html><body><p>Full</p><p>Page</p></body></html>"
+			}
+		}
+	}`)
+
+	expectedOutput := OutputJson{
+		"//p/text()": {
+			"http://example.com/fragment": "Hello",
+			"http://example.com/page":     "Full",
+		},
+		// The fragment's wrapper makes absolute paths address it like a full page
+		"/html/body/p[2]": {
+			"http://example.com/fragment": "World",
+			"http://example.com/page":     "Page",
+		},
+	}
+
+	actualOutput, err := processInput(inputJsonBytes)
+	if err != nil {
+		t.Fatalf("processInput returned an unexpected error: %v", err)
+	}
+
+	if !reflect.DeepEqual(expectedOutput, actualOutput) {
+		expectedJson, _ := json.MarshalIndent(expectedOutput, "", "  ")
+		actualJson, _ := json.MarshalIndent(actualOutput, "", "  ")
+		t.Errorf("Unexpected output for fragment content.\nExpected:\n%s\nGot:\n%s", string(expectedJson), string(actualJson))
+	}
+}
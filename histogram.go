@@ -0,0 +1,69 @@
+package main
+
+import (
+	"fmt"
+	"io"
+	"sort"
+	"strings"
+)
+
+// matchCountLabels name the buckets of a match histogram, by nodes matched per URL.
+var matchCountLabels = [...]string{"0", "1", "2-5", "6+"}
+
+// matchHistogram counts, for one selector, the URLs falling in each bucket.
+type matchHistogram [len(matchCountLabels)]int
+
+// matchCountBucket returns the index of the bucket for n matched nodes.
+func matchCountBucket(n int) int {
+	switch {
+	case n <= 1:
+		return n
+	case n <= 5:
+		return 2
+	default:
+		return 3
+	}
+}
+
+// matchHistograms collects, during processing, a matchHistogram per compiled
+// selector, keyed by XPath. Only evaluated pairs are counted: pairs the run skips
+// are not, and a URL that failed to load or has no tree counts as matching nothing.
+// A checkpoint carries the counts of the URLs it completed.
+// A nil matchHistograms records nothing.
+type matchHistograms map[string]*matchHistogram
+
+func newMatchHistograms(compiledPaths map[string]*compiledSelector) matchHistograms {
+	histograms := make(matchHistograms, len(compiledPaths))
+	for xpathStr := range compiledPaths {
+		histograms[xpathStr] = &matchHistogram{}
+	}
+	return histograms
+}
+
+// add counts n nodes matched by the selector's XPath on one URL. Selectors
+// without a histogram, which did not compile, are ignored.
+func (h matchHistograms) add(xpathStr string, n int) {
+	if histogram := h[xpathStr]; histogram != nil {
+		histogram[matchCountBucket(n)]++
+	}
+}
+
+// write prints one line per selector, sorted by XPath.
+func (h matchHistograms) write(w io.Writer) {
+	if h == nil {
+		return
+	}
+	xpaths := make([]string, 0, len(h))
+	for xpathStr := range h {
+		xpaths = append(xpaths, xpathStr)
+	}
+	sort.Strings(xpaths)
+
+	for _, xpathStr := range xpaths {
+		buckets := make([]string, len(matchCountLabels))
+		for i, label := range matchCountLabels {
+			buckets[i] = fmt.Sprintf("%s=%d", label, h[xpathStr][i])
+		}
+		fmt.Fprintf(w, "%s %s\n", xpathStr, strings.Join(buckets, " "))
+	}
+}
@@ -0,0 +1,41 @@
+package main
+
+import (
+	"bytes"
+	"context"
+	"testing"
+)
+
+// Test case for bucketing per-URL match counts of each selector
+func TestProcessInput_MatchHistogram(t *testing.T) {
+	inputJsonBytes := []byte(`{
+		"xpaths": [
+			"//li", "//title", "//li",
+			{"xpath": "//ul", "exclude_urls": ["*/none", "*/broken"]},
+			{"xpath": "//p", "when": "//p"}
+		],
+		"urls": {
+			"http://example.com/none": {"content": "<html><body><p>No list</p></body></html>"},
+			"http://example.com/one": {"content": "<html><body><ul><li>1</li></ul></body></html>"},
+			"http://example.com/few": {"content": "<html><body><ul><li>1</li><li>2</li><li>3</li></ul></body></html>"},
+			"http://example.com/many": {"content": "<html><body><ul><li>1</li><li>2</li><li>3</li><li>4</li><li>5</li><li>6</li><li>7</li></ul></body></html>"},
+			"http://example.com/broken": {"content": ""}
+		}
+	}`)
+
+	var report bytes.Buffer
+	if _, err := processInputWithOptions(context.Background(), inputJsonBytes, Options{MatchHistogram: &report}); err != nil {
+		t.Fatalf("processInputWithOptions returned an unexpected error: %v", err)
+	}
+
+	// Every URL falls in exactly one bucket, the one that failed to parse in "0";
+	// the duplicate //li is reported once. Pairs skipped by exclude_urls or a failed
+	// when are not counted
+	want := "//li 0=2 1=1 2-5=1 6+=1\n" +
+		"//p 0=1 1=1 2-5=0 6+=0\n" +
+		"//title 0=5 1=0 2-5=0 6+=0\n" +
+		"//ul 0=0 1=3 2-5=0 6+=0\n"
+	if report.String() != want {
+		t.Errorf("Unexpected histogram report.\nExpected:\n%s\nGot:\n%s", want, report.String())
+	}
+}
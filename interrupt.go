@@ -0,0 +1,40 @@
+package main
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"os"
+)
+
+// errInterrupted is the cancellation cause when the run is interrupted with Ctrl-C.
+var errInterrupted = errors.New("interrupted")
+
+// exitInterrupted is the exit status of an interrupted run, as shells report SIGINT.
+const exitInterrupted = 130
+
+// cancelOnInterrupt returns a context cancelled with errInterrupted when the first
+// signal arrives on signals, so processing stops between URLs and partial results
+// can be printed. A second signal exits immediately. Call stop once the run is over.
+func cancelOnInterrupt(parent context.Context, signals <-chan os.Signal) (ctx context.Context, stop func()) {
+	ctx, cancel := context.WithCancelCause(parent)
+	done := make(chan struct{})
+	go func() {
+		select {
+		case <-signals:
+		case <-done:
+			return
+		}
+		fmt.Fprintf(os.Stderr, "Interrupted: finishing the current URL. Press Ctrl-C again to exit immediately.\n")
+		cancel(errInterrupted)
+		select {
+		case <-signals:
+			os.Exit(exitInterrupted)
+		case <-done:
+		}
+	}()
+	return ctx, func() {
+		close(done)
+		cancel(nil)
+	}
+}
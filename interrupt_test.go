@@ -0,0 +1,56 @@
+package main
+
+import (
+	"context"
+	"errors"
+	"os"
+	"reflect"
+	"testing"
+	"time"
+
+	"launchpad.net/xmlpath"
+)
+
+// Test case for an interrupt stopping the run with partial results
+func TestProcessInput_Interrupt(t *testing.T) {
+	signals := make(chan os.Signal, 1)
+	ctx, stop := cancelOnInterrupt(context.Background(), signals)
+	defer stop()
+
+	// Deliver Ctrl-C while the first URL is being evaluated
+	original := evaluateBytes
+	defer func() { evaluateBytes = original }()
+	interrupted := false
+	evaluateBytes = func(path *xmlpath.Path, root *xmlpath.Node) ([]byte, bool) {
+		if !interrupted {
+			interrupted = true
+			signals <- os.Interrupt
+			select {
+			case <-ctx.Done():
+			case <-time.After(5 * time.Second):
+				t.Errorf("Expected the interrupt to cancel the context")
+			}
+		}
+		return original(path, root)
+	}
+
+	inputJsonBytes := []byte(`{
+		"xpaths": ["/html/body/p"],
+		"urls": {
+			"http://example.com/1": {"content": "<html><body><p>One</p></body></html>"},
+			"http://example.com/2": {"content": "<html><body><p>Two</p></body></html>"},
+			"http://example.com/3": {"content": "<html><body><p>Three</p></body></html>"}
+		}
+	}`)
+
+	actualOutput, err := processInputWithOptions(ctx, inputJsonBytes, Options{})
+	if !errors.Is(err, errInterrupted) {
+		t.Fatalf("Expected an interrupted error, got %v", err)
+	}
+
+	// The URL in progress finishes; later ones are not started
+	expectedOutput := OutputJson{"/html/body/p": {"http://example.com/1": "One"}}
+	if !reflect.DeepEqual(expectedOutput, actualOutput) {
+		t.Errorf("Unexpected partial output.\nExpected: %v\nGot: %v", expectedOutput, actualOutput)
+	}
+}
@@ -0,0 +1,77 @@
+package main
+
+import (
+	"bufio"
+	"bytes"
+	"context"
+	"encoding/json"
+	"errors"
+	"io"
+)
+
+// lineRecord is one self-contained check in --json-lines mode, read from a single
+// line of input (see schemas/line.schema.json).
+type lineRecord struct {
+	Url     string     `json:"url"`
+	Content string     `json:"content"`
+	Xpaths  []Selector `json:"xpaths"`
+}
+
+// lineResult is the output line for one lineRecord: the value extracted by each
+// matching XPath, or the reason the line could not be processed.
+type lineResult struct {
+	Url     string            `json:"url"`
+	Results map[string]string `json:"results"`
+	Error   string            `json:"error,omitempty"`
+}
+
+// runJsonLines processes one lineRecord per line of in, writing one lineResult per
+// record to out in the same order. Blank lines are skipped; malformed lines yield
+// a result with Error set, so output lines always correspond to input records.
+func runJsonLines(ctx context.Context, in io.Reader, out io.Writer, opts Options) error {
+	reader := bufio.NewReader(in) // Not a Scanner: lines carry whole pages and can be long
+	encoder := json.NewEncoder(out)
+	for {
+		line, readErr := reader.ReadBytes('\n')
+		if readErr != nil && !errors.Is(readErr, io.EOF) {
+			return readErr
+		}
+
+		if line = bytes.TrimSpace(line); len(line) > 0 {
+			if err := encoder.Encode(processLine(ctx, line, opts)); err != nil {
+				return err
+			}
+		}
+
+		if readErr != nil {
+			return nil // EOF
+		}
+	}
+}
+
+// processLine evaluates a single record line.
+func processLine(ctx context.Context, line []byte, opts Options) lineResult {
+	var rec lineRecord
+	if err := json.Unmarshal(line, &rec); err != nil {
+		return lineResult{Results: map[string]string{}, Error: "error unmarshalling record: " + err.Error()}
+	}
+
+	input := InputJson{
+		Xpaths: rec.Xpaths,
+		Urls:   map[string]UrlData{rec.Url: {Content: rec.Content}},
+	}
+	run, err := runInput(ctx, input, opts)
+	result := lineResult{Url: rec.Url, Results: make(map[string]string)}
+	for xpathStr, values := range run.output {
+		if value, ok := values[rec.Url]; ok {
+			result.Results[xpathStr] = value
+		}
+	}
+	if err != nil {
+		result.Error = err.Error()
+	} else if urlErr, ok := run.urlErrors[rec.Url]; ok {
+		// The content could not be loaded or parsed, so no selector was evaluated
+		result.Error = urlErr
+	}
+	return result
+}
@@ -0,0 +1,50 @@
+package main
+
+import (
+	"bytes"
+	"context"
+	"encoding/json"
+	"reflect"
+	"strings"
+	"testing"
+)
+
+// Test case for record-per-line streaming
+func TestRunJsonLines(t *testing.T) {
+	in := strings.NewReader(`{"url": "http://example.com", "content": "<html><head><title>Test Page</title></head><body><p>Hello</p></body></html>", "xpaths": ["//title", "//nonexistent"]}
+{"url": "http://other.com", "content": "<html><body><p>Other</p></body></html>", "xpaths": ["/html/body/p"]}
+{"url": "http://broken.com", "content": "", "xpaths": ["//title"]}
+`)
+
+	var out bytes.Buffer
+	if err := runJsonLines(context.Background(), in, &out, Options{}); err != nil {
+		t.Fatalf("runJsonLines returned an unexpected error: %v", err)
+	}
+
+	expectedResults := []lineResult{
+		{Url: "http://example.com", Results: map[string]string{"//title": "Test Page"}},
+		{Url: "http://other.com", Results: map[string]string{"/html/body/p": "Other"}},
+		{Url: "http://broken.com", Results: map[string]string{}}, // Error checked below
+	}
+
+	lines := strings.Split(strings.TrimRight(out.String(), "\n"), "\n")
+	if len(lines) != len(expectedResults) {
+		t.Fatalf("Expected %d result lines, got %d:\n%s", len(expectedResults), len(lines), out.String())
+	}
+	for i, line := range lines {
+		var actual lineResult
+		if err := json.Unmarshal([]byte(line), &actual); err != nil {
+			t.Fatalf("Failed to parse result line %q: %v", line, err)
+		}
+		if actual.Url == "http://broken.com" {
+			// Content that fails to parse is reported on its line, not just on stderr
+			if !strings.HasPrefix(actual.Error, "parsing: ") {
+				t.Errorf("Expected a parse error for line %d, got %q", i+1, actual.Error)
+			}
+			actual.Error = ""
+		}
+		if !reflect.DeepEqual(expectedResults[i], actual) {
+			t.Errorf("Unexpected result for line %d.\nExpected: %+v\nGot: %+v", i+1, expectedResults[i], actual)
+		}
+	}
+}
@@ -0,0 +1,133 @@
+package main
+
+import (
+	"fmt"
+	"io"
+	"regexp"
+	"sort"
+	"strings"
+
+	"launchpad.net/xmlpath"
+)
+
+// broadMatchFraction is the share of a page's elements a selector may match
+// before it is reported as too broad.
+const broadMatchFraction = 0.5
+
+// genericXpath matches selectors that select by bare element name or wildcard
+// anywhere in the document, with no predicate or parent to narrow them.
+var genericXpath = regexp.MustCompile(`^//(\*|node\(\)|[A-Za-z_][\w.-]*)$`)
+
+// allElements counts every element in a tree.
+var allElements = xmlpath.MustCompile("//*")
+
+// selectorLint collects, during processing, warnings for selectors that look like
+// authoring mistakes: generic patterns such as //* or //div, and selectors matching
+// more than broadMatchFraction of the elements on some page.
+// A nil *selectorLint records nothing.
+type selectorLint struct {
+	selectors []Selector
+	warnings  map[string]string // The first warning for each XPath
+}
+
+// newSelectorLint flags the generic selectors up front; the others are checked as
+// pages are evaluated.
+func newSelectorLint(selectors []Selector) *selectorLint {
+	l := &selectorLint{selectors: selectors, warnings: make(map[string]string)}
+	for _, sel := range selectors {
+		if genericXpath.MatchString(strings.TrimSpace(sel.Xpath)) {
+			l.warnings[sel.Xpath] = fmt.Sprintf("selector %s is very generic; narrow it with a predicate such as [@id='...'] or a parent path", sel.label())
+		}
+	}
+	return l
+}
+
+// checkPage measures each compiled selector's XPath against the elements of one page.
+func (l *selectorLint) checkPage(url string, root *xmlpath.Node, compiledPaths map[string]*compiledSelector) {
+	if l == nil {
+		return
+	}
+	total := countMatches(allElements, root)
+	if total == 0 {
+		return
+	}
+	for xpathStr, compiled := range compiledPaths {
+		if _, ok := l.warnings[xpathStr]; ok {
+			continue
+		}
+		matched := countMatches(compiled.path, root)
+		if float64(matched) > broadMatchFraction*float64(total) {
+			l.warnings[xpathStr] = fmt.Sprintf("selector %s matches %d of %d elements on '%s'; it is likely too broad", compiled.label(), matched, total, url)
+		}
+	}
+}
+
+// write prints the warnings in selector order.
+func (l *selectorLint) write(w io.Writer) {
+	if l == nil {
+		return
+	}
+	for _, sel := range l.selectors {
+		if warning, ok := l.warnings[sel.Xpath]; ok {
+			fmt.Fprintf(w, "Warning: %s\n", warning)
+		}
+	}
+}
+
+// countMatches returns the number of nodes path selects from root.
+func countMatches(path *xmlpath.Path, root *xmlpath.Node) int {
+	n := 0
+	for iter := path.Iter(root); iter.Next(); {
+		n++
+	}
+	return n
+}
+
+// checkRequiredMatches fails, for --require-matches, if any selector produced no
+// value on any URL, listing those dead selectors.
+func checkRequiredMatches(output OutputJson) error {
+	var dead []string
+	for xpathStr, results := range output {
+		if len(results) == 0 {
+			dead = append(dead, xpathStr)
+		}
+	}
+	if len(dead) == 0 {
+		return nil
+	}
+	sort.Strings(dead)
+	return fmt.Errorf("%d selector(s) matched nothing on any URL: '%s'", len(dead), strings.Join(dead, "', '"))
+}
+
+// checkSuccessThresholds fails if any selector produced a value on fewer of the
+// URLs it was evaluated on than its min_success_count or min_success_ratio
+// requires, listing each such selector with its tally. Skipped pairs do not count.
+func checkSuccessThresholds(run runResult) error {
+	var failures []string
+	for _, sel := range run.selectors {
+		if sel.MinSuccessCount == 0 && sel.MinSuccessRatio == 0 {
+			continue
+		}
+		evaluated, succeeded := 0, 0
+		for _, url := range run.urls {
+			if run.isSkipped(sel.Xpath, url) {
+				continue
+			}
+			evaluated++
+			if _, ok := run.output[sel.Xpath][url]; ok {
+				succeeded++
+			}
+		}
+		ratio := 0.0
+		if evaluated > 0 {
+			ratio = float64(succeeded) / float64(evaluated)
+		}
+		if succeeded < sel.MinSuccessCount || ratio < sel.MinSuccessRatio {
+			failures = append(failures, fmt.Sprintf("%s matched %d of %d URLs (min_success_count %d, min_success_ratio %g)", sel.label(), succeeded, evaluated, sel.MinSuccessCount, sel.MinSuccessRatio))
+		}
+	}
+	if len(failures) == 0 {
+		return nil
+	}
+	return fmt.Errorf("%d selector(s) below their success threshold: %s", len(failures), strings.Join(failures, "; "))
+}
@@ -0,0 +1,112 @@
+package main
+
+import (
+	"bytes"
+	"context"
+	"strings"
+	"testing"
+)
+
+// Test case for flagging likely-too-broad selectors
+func TestProcessInput_LintReport(t *testing.T) {
+	inputJsonBytes := []byte(`{
+		"xpaths": ["//*", "//a[@id='x']", "/html/body/ul/li"],
+		"urls": {
+			"http://example.com": {
+				"content": "<html><body><ul><li>1</li><li>2</li><li>3</li><li>4</li><li>5</li><li>6</li><li>7</li></ul><a id=\"x\">Link</a></body></html>"
+			}
+		}
+	}`)
+
+	var report bytes.Buffer
+	if _, err := processInputWithOptions(context.Background(), inputJsonBytes, Options{LintReport: &report}); err != nil {
+		t.Fatalf("processInputWithOptions returned an unexpected error: %v", err)
+	}
+	warnings := report.String()
+
+	// A bare wildcard is generic by pattern alone
+	if !strings.Contains(warnings, "Warning: selector '//*' is very generic") {
+		t.Errorf("Expected a broad-selector warning for //*, got:\n%s", warnings)
+	}
+	// A specific selector is not flagged
+	if strings.Contains(warnings, "'//a[@id='x']'") {
+		t.Errorf("Expected no warning for //a[@id='x'], got:\n%s", warnings)
+	}
+	// 7 of the page's 11 elements are list items
+	if !strings.Contains(warnings, "Warning: selector '/html/body/ul/li' matches 7 of 11 elements on 'http://example.com'") {
+		t.Errorf("Expected a match-fraction warning for /html/body/ul/li, got:\n%s", warnings)
+	}
+	// Warnings follow the selector order
+	if strings.Index(warnings, "'//*'") > strings.Index(warnings, "'/html/body/ul/li'") {
+		t.Errorf("Expected warnings in selector order, got:\n%s", warnings)
+	}
+}
+
+// Test case for failing the run on selectors that never match
+func TestCheckRequiredMatches(t *testing.T) {
+	inputJsonBytes := []byte(`{
+		"xpaths": ["//title", "//nonexistent", "//missing"],
+		"urls": {
+			"http://example.com": {"content": "<html><head><title>Test Page</title></head></html>"}
+		}
+	}`)
+
+	output, err := processInput(inputJsonBytes)
+	if err != nil {
+		t.Fatalf("processInput returned an unexpected error: %v", err)
+	}
+
+	err = checkRequiredMatches(output)
+	if err == nil {
+		t.Fatalf("Expected an error for never-matching selectors, got nil")
+	}
+	for _, dead := range []string{"'//missing'", "'//nonexistent'"} {
+		if !strings.Contains(err.Error(), dead) {
+			t.Errorf("Expected the error to list %s, got: %v", dead, err)
+		}
+	}
+	if strings.Contains(err.Error(), "//title") {
+		t.Errorf("Expected a matching selector not to be listed, got: %v", err)
+	}
+
+	// Once every selector matches somewhere the check passes
+	delete(output, "//nonexistent")
+	delete(output, "//missing")
+	if err := checkRequiredMatches(output); err != nil {
+		t.Errorf("Expected no error when every selector matched, got: %v", err)
+	}
+}
+
+// Test case for failing the run on selectors below their success threshold
+func TestCheckSuccessThresholds(t *testing.T) {
+	inputJsonBytes := []byte(`{
+		"xpaths": [
+			{"xpath": "//span[@class='sale']", "min_success_ratio": 0.9},
+			{"xpath": "//title", "min_success_count": 3},
+			"//nonexistent"
+		],
+		"urls": {
+			"http://shop.com/a": {"content": "<html><head><title>A</title></head><body><span class=\"sale\">-10%</span></body></html>"},
+			"http://shop.com/b": {"content": "<html><head><title>B</title></head><body></body></html>"},
+			"http://shop.com/c": {"content": "<html><head><title>C</title></head><body></body></html>"}
+		}
+	}`)
+
+	input, err := unmarshalInput(inputJsonBytes, Options{})
+	if err != nil {
+		t.Fatalf("unmarshalInput returned an unexpected error: %v", err)
+	}
+	run, err := runInput(context.Background(), input, Options{})
+	if err != nil {
+		t.Fatalf("runInput returned an unexpected error: %v", err)
+	}
+
+	// 1 of 3 URLs is below 0.9; //title meets its count and //nonexistent has no threshold
+	err = checkSuccessThresholds(run)
+	if err == nil {
+		t.Fatalf("Expected an error for a selector below its success ratio, got nil")
+	}
+	if !strings.Contains(err.Error(), "1 selector(s)") || !strings.Contains(err.Error(), "'//span[@class='sale']' matched 1 of 3 URLs") {
+		t.Errorf("Expected the error to report //span[@class='sale'] with its tally, got: %v", err)
+	}
+}
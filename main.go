@@ -1,12 +1,24 @@
 package main
 
 import (
+	"bytes"
+	"context"
+	"crypto/sha1"
+	"encoding/hex"
 	"encoding/json"
 	"encoding/xml"
+	"errors"
+	"flag"
 	"fmt"
 	"io"
 	"os"
+	"os/signal"
+	"path/filepath"
+	"regexp"
+	"sort"
 	"strings"
+	"time"
+	"unicode"
 
 	"golang.org/x/net/html/charset" // For character encoding detection
 	"launchpad.net/xmlpath"        // The XPath library used by xpup
@@ -15,12 +27,172 @@ import (
 // --- Input Structures ---
 
 type InputJson struct {
-	Xpaths []string          `json:"xpaths"`
+	Xpaths []Selector         `json:"xpaths"`
 	Urls   map[string]UrlData `json:"urls"`
+	// NormalizeTagCase lowercases element names in both the parsed content and the
+	// XPaths, making element matching case-insensitive.
+	NormalizeTagCase bool `json:"normalize_tag_case,omitempty"`
+	// AllowedTags, when present, lists the only element names content may contain;
+	// a URL whose content has any other element is skipped.
+	AllowedTags []string `json:"allowed_tags,omitempty"`
+	// OnDuplicateUrl selects what happens when a URL key appears more than once in
+	// "urls": "error" rejects the input, "warn" logs it; either way the last entry is
+	// used. Absent, duplicates are not checked.
+	OnDuplicateUrl string `json:"on_duplicate_url,omitempty"`
+	// EvalTimeoutMs bounds each XPath evaluation; a result that takes longer is
+	// omitted with a warning. Zero means no limit.
+	EvalTimeoutMs int `json:"eval_timeout_ms,omitempty"`
+	// InvalidEncoding selects what happens to content that is not valid UTF-8 (such
+	// as a fixture file): "error" (the default) skips the URL, "replace" substitutes
+	// U+FFFD for the invalid bytes.
+	InvalidEncoding string `json:"invalid_encoding,omitempty"`
+	// CanonicalizeXpaths rewrites XPaths to a canonical spelling (quote style,
+	// whitespace) before processing, so equivalent selectors share one output key.
+	CanonicalizeXpaths bool `json:"canonicalize_xpaths,omitempty"`
+}
+
+// Selector is one "xpaths" entry: either a plain XPath string or an object with
+// the XPath under "xpath" plus optional per-selector settings.
+type Selector struct {
+	Xpath string `json:"xpath"`
+	// Description says what the selector is for; it is included in messages about it.
+	Description string `json:"description,omitempty"`
+	// Transform is a sequence of ops applied in order to the extracted value:
+	// "upper", "lower", "trim", "reverse" or "replace:<from>:<to>".
+	Transform []string `json:"transform,omitempty"`
+	// Fallbacks are XPaths tried in order when Xpath matches nothing; the first to
+	// match supplies the value. Results stay keyed by Xpath.
+	Fallbacks []string `json:"fallbacks,omitempty"`
+	// ExtractRegex narrows the extracted value to the regex's first capture group
+	// (or whole match). A value the regex does not match is treated as no match.
+	ExtractRegex string `json:"extract_regex,omitempty"`
+	// UrlPolicy "first_match" stops evaluating the selector once one URL (in sorted
+	// order) produces a value, for redundant sources such as mirrors. The default,
+	// "all", evaluates every URL.
+	UrlPolicy string `json:"url_policy,omitempty"`
+	// Tags group selectors so a run can be limited to some of them with --tag.
+	Tags []string `json:"tags,omitempty"`
+	// Attributes, if set, make the value the named attributes of the first matched
+	// element joined by AttributeSeparator (a space by default). An element missing
+	// any of them is reported and gives no value.
+	Attributes         []string `json:"attributes,omitempty"`
+	AttributeSeparator string   `json:"attribute_separator,omitempty"`
+	// When is a precondition XPath: on URLs where it matches nothing the selector
+	// is not evaluated, e.g. to check a cart button only on in-stock pages.
+	When string `json:"when,omitempty"`
+	// Concat lists XPaths whose values are appended, in order, to the selector's
+	// value, joined by ConcatSeparator (a space by default), e.g. for a name split
+	// across spans. If any of them matches nothing there is no value.
+	Concat          []string `json:"concat,omitempty"`
+	ConcatSeparator string   `json:"concat_separator,omitempty"`
+	// ExcludeUrls are globs (* matches anything, including '/') of URLs the
+	// selector is not evaluated against, e.g. "*/admin/*".
+	ExcludeUrls []string `json:"exclude_urls,omitempty"`
+	// ExpectType names a validator ("email", "url", "uuid", "integer" or
+	// "iso_date") the final value must pass. Values that fail are reported and
+	// omitted.
+	ExpectType string `json:"expect_type,omitempty"`
+	// ResolveUrls resolves relative URL values, such as hrefs, to absolute ones
+	// against the page's base_url, or its URL if it has none.
+	ResolveUrls bool `json:"resolve_urls,omitempty"`
+	// ExpectDistinctCount, if positive, is the number of distinct values the nodes
+	// matched by the XPath must collapse to, e.g. 3 for three unique tab labels.
+	// A URL where the count differs is reported and gives no value.
+	ExpectDistinctCount int `json:"expect_distinct_count,omitempty"`
+	// MinSuccessCount and MinSuccessRatio are thresholds on the number and share of
+	// the URLs the selector is evaluated on where it must produce a value; a run
+	// that falls short fails once all URLs are processed (see checkSuccessThresholds).
+	MinSuccessCount int     `json:"min_success_count,omitempty"`
+	MinSuccessRatio float64 `json:"min_success_ratio,omitempty"`
+}
+
+// Values for Selector.UrlPolicy.
+const (
+	urlPolicyAll        = "all"
+	urlPolicyFirstMatch = "first_match"
+)
+
+func (s *Selector) UnmarshalJSON(data []byte) error {
+	var xpathStr string
+	if err := json.Unmarshal(data, &xpathStr); err == nil {
+		*s = Selector{Xpath: xpathStr}
+		return nil
+	}
+	type plainSelector Selector // Avoids recursing into this method
+	return json.Unmarshal(data, (*plainSelector)(s))
+}
+
+// label returns the selector's XPath quoted for messages, with its description if any.
+func (s Selector) label() string {
+	if s.Description == "" {
+		return fmt.Sprintf("'%s'", s.Xpath)
+	}
+	return fmt.Sprintf("'%s' (%s)", s.Xpath, s.Description)
 }
 
 type UrlData struct {
 	Content string `json:"content"`
+	// Render requests that the page be rendered by the external renderer
+	// (see Options.RendererURL) and the rendered HTML used instead of Content.
+	Render bool `json:"render,omitempty"`
+	// ContentType is "html" (the default) or "text". For plain text, each selector's
+	// XPath string is used as a regex and the first matching line is reported.
+	ContentType string `json:"content_type,omitempty"`
+	// ParseMode is "document" (the default) or "fragment" for markup without an
+	// enclosing <html><body>; see applyParseMode.
+	ParseMode string `json:"parse_mode,omitempty"`
+	// BaseUrl, if set, replaces the page URL as the base for resolve_urls.
+	BaseUrl string `json:"base_url,omitempty"`
+}
+
+// --- Options ---
+
+// Options holds settings supplied on the command line rather than in the input JSON.
+type Options struct {
+	// ContentDir is a directory of fixture files named <sha1(url)>.html, consulted for
+	// URLs without inline content.
+	ContentDir string
+	// LenientUrls decodes "urls" entry by entry, skipping malformed entries instead
+	// of rejecting the whole input.
+	LenientUrls bool
+	// Tags limits the run to selectors carrying these tags, combined per TagMode
+	// ("any" or "all"). Untagged selectors only run if IncludeUntagged is set.
+	Tags            []string
+	TagMode         string
+	IncludeUntagged bool
+	// TimingReport, if set, receives a per-URL and per-selector timing breakdown
+	// once processing finishes.
+	TimingReport io.Writer
+	// MemoryReport, if set, receives the peak heap usage and bytes allocated
+	// once processing finishes.
+	MemoryReport io.Writer
+	// LintReport, if set, receives warnings for selectors that look too broad
+	// once processing finishes.
+	LintReport io.Writer
+	// MatchHistogram, if set, receives for each selector how many of the URLs it was
+	// evaluated on it matched 0, 1, 2-5 and 6+ nodes on once processing finishes.
+	MatchHistogram io.Writer
+	// Checkpoint, if set, is a file recording completed URLs and their results, so an
+	// interrupted run started again with the same input resumes where it stopped.
+	Checkpoint string
+	// RendererURL is the headless browser endpoint used for URLs with "render": true.
+	// When empty, rendering is disabled and the static content is always used.
+	RendererURL string
+
+	// The remaining options are applied by main around processing.
+
+	// Format selects the output shape; see shapeOutput.
+	Format string
+	// Compact emits single-line JSON instead of indented output.
+	Compact bool
+	// WithProvenance wraps the results with the input's hash and the resolved
+	// configuration.
+	WithProvenance bool
+	// WithRunMeta wraps the output with a generated run id and the run's start and
+	// finish times.
+	WithRunMeta bool
+	// MaxRuntime is the overall time budget for processing; zero means unlimited.
+	MaxRuntime time.Duration
 }
 
 // --- Output Structures ---
@@ -30,13 +202,39 @@ type OutputJson map[string]map[string]string
 
 // --- Helper Functions ---
 
+// errRuntimeExceeded is the cancellation cause when --max-runtime expires.
+var errRuntimeExceeded = errors.New("runtime exceeded")
+
 func fatalf(format string, a ...interface{}) {
 	fmt.Fprintf(os.Stderr, format, a...)
 	os.Exit(2)
 }
 
+// parseOptions controls how content is turned into a tree.
+type parseOptions struct {
+	// normalizeTagCase lowercases element names as they are parsed.
+	normalizeTagCase bool
+	// allowedTags, if non-nil, rejects content containing any other element.
+	allowedTags map[string]bool
+}
+
+// parseOptionsFor returns the parse options requested by the input.
+func parseOptionsFor(input InputJson) parseOptions {
+	popts := parseOptions{normalizeTagCase: input.NormalizeTagCase}
+	if input.AllowedTags != nil {
+		popts.allowedTags = make(map[string]bool, len(input.AllowedTags))
+		for _, tag := range input.AllowedTags {
+			if input.NormalizeTagCase {
+				tag = strings.ToLower(tag)
+			}
+			popts.allowedTags[tag] = true
+		}
+	}
+	return popts
+}
+
 // decode reads from the reader, attempts to detect charset, and parses XML
-func decode(r io.Reader) (*xmlpath.Node, error) {
+func decode(r io.Reader, popts parseOptions) (*xmlpath.Node, error) {
 	decoder := xml.NewDecoder(r)
 	// Use charset reader similar to xpup to handle different encodings
 	decoder.CharsetReader = func(chset string, input io.Reader) (io.Reader, error) {
@@ -45,98 +243,1105 @@ func decode(r io.Reader) (*xmlpath.Node, error) {
 		// If charset is empty, it might try to auto-detect or default to UTF-8.
 		return charset.NewReader(input, chset)
 	}
-	return xmlpath.ParseDecoder(decoder)
+	if !popts.normalizeTagCase && popts.allowedTags == nil {
+		return xmlpath.ParseDecoder(decoder)
+	}
+
+	var tokens xml.TokenReader = decoder
+	if popts.normalizeTagCase {
+		tokens = lowercaseTagReader{tokens}
+	}
+	if popts.allowedTags != nil {
+		tokens = allowedTagReader{tokens, popts.allowedTags}
+	}
+	return xmlpath.ParseDecoder(xml.NewTokenDecoder(tokens))
+}
+
+// allowedTagReader wraps a token stream, failing on any element not in allowed.
+type allowedTagReader struct {
+	tokens  xml.TokenReader
+	allowed map[string]bool
+}
+
+func (a allowedTagReader) Token() (xml.Token, error) {
+	tok, err := a.tokens.Token()
+	if start, ok := tok.(xml.StartElement); ok && !a.allowed[start.Name.Local] {
+		return nil, fmt.Errorf("disallowed tag <%s>", start.Name.Local)
+	}
+	return tok, err
+}
+
+// lowercaseTagReader wraps a token stream, lowercasing element names.
+type lowercaseTagReader struct {
+	tokens xml.TokenReader
+}
+
+func (l lowercaseTagReader) Token() (xml.Token, error) {
+	tok, err := l.tokens.Token()
+	switch t := tok.(type) {
+	case xml.StartElement:
+		t.Name.Local = strings.ToLower(t.Name.Local)
+		tok = t
+	case xml.EndElement:
+		t.Name.Local = strings.ToLower(t.Name.Local)
+		tok = t
+	}
+	return tok, err
+}
+
+// lowercaseXpathNames lowercases the element names in an XPath to match content
+// parsed with normalizeTagCase. String literals and attribute names are left as written.
+func lowercaseXpathNames(xpathStr string) string {
+	var b strings.Builder
+	var quote rune
+	inAttr := false
+	for _, r := range xpathStr {
+		switch {
+		case quote != 0:
+			if r == quote {
+				quote = 0
+			}
+		case r == '\'' || r == '"':
+			quote = r
+		case r == '@':
+			inAttr = true
+		case inAttr && isXpathNameChar(r):
+			// Attribute names keep their case
+		default:
+			inAttr = false
+			r = unicode.ToLower(r)
+		}
+		b.WriteRune(r)
+	}
+	return b.String()
+}
+
+// isXpathNameChar reports whether r can appear in an XML name.
+func isXpathNameChar(r rune) bool {
+	return unicode.IsLetter(r) || unicode.IsDigit(r) || r == '-' || r == '_' || r == '.' || r == ':'
+}
+
+// dedupXpaths returns selectors with repeated XPath strings removed, keeping the
+// first occurrence, and logs a warning for each duplicate dropped.
+func dedupXpaths(selectors []Selector) []Selector {
+	seen := make(map[string]bool, len(selectors))
+	unique := make([]Selector, 0, len(selectors))
+	for _, sel := range selectors {
+		if seen[sel.Xpath] {
+			fmt.Fprintf(os.Stderr, "Warning: Duplicate XPath %s in input. Evaluating it once.\n", sel.label())
+			continue
+		}
+		seen[sel.Xpath] = true
+		unique = append(unique, sel)
+	}
+	return unique
+}
+
+// resolveContent returns the markup to evaluate for a URL: the rendered page when
+// rendering is requested, the inline content if present, or else the fixture file
+// from the content directory.
+func resolveContent(ctx context.Context, url string, urlData UrlData, opts Options) (string, error) {
+	if urlData.Render {
+		if opts.RendererURL != "" {
+			return renderURL(ctx, opts.RendererURL, url)
+		}
+		fmt.Fprintf(os.Stderr, "Warning: URL '%s' requests rendering but no renderer URL was given. Using static content.\n", url)
+	}
+	if urlData.Content != "" || opts.ContentDir == "" {
+		return urlData.Content, nil
+	}
+
+	path := fixturePath(opts.ContentDir, url)
+	fixture, err := os.ReadFile(path)
+	if err != nil {
+		return "", fmt.Errorf("no inline content and no fixture file: %w", err)
+	}
+	return string(fixture), nil
+}
+
+// fixturePath returns the path of a URL's fixture file: <dir>/<sha1(url)>.html
+func fixturePath(dir, url string) string {
+	sum := sha1.Sum([]byte(url))
+	return filepath.Join(dir, hex.EncodeToString(sum[:])+".html")
+}
+
+// unmarshalInputLenient decodes the input like json.Unmarshal, except that each
+// "urls" entry is decoded on its own and malformed entries are logged and skipped
+// rather than failing the whole input.
+func unmarshalInputLenient(inputBytes []byte) (InputJson, error) {
+	var raw struct {
+		InputJson
+		Urls map[string]json.RawMessage `json:"urls"`
+	}
+	if err := json.Unmarshal(inputBytes, &raw); err != nil {
+		return InputJson{}, err
+	}
+
+	input := raw.InputJson
+	input.Urls = make(map[string]UrlData, len(raw.Urls))
+	for url, entry := range raw.Urls {
+		var urlData UrlData
+		if err := json.Unmarshal(entry, &urlData); err != nil {
+			fmt.Fprintf(os.Stderr, "Warning: Failed to decode entry for URL '%s': %v. Skipping this URL.\n", url, err)
+			continue
+		}
+		input.Urls[url] = urlData
+	}
+	return input, nil
+}
+
+// checkDuplicateUrls applies the on_duplicate_url policy to URL keys that appear
+// more than once in the raw input, which json.Unmarshal would silently collapse.
+func checkDuplicateUrls(inputBytes []byte, policy string) error {
+	if policy == "" {
+		return nil
+	}
+	if policy != "error" && policy != "warn" {
+		return fmt.Errorf("invalid on_duplicate_url %q: expected \"error\" or \"warn\"", policy)
+	}
+
+	var raw struct {
+		Urls json.RawMessage `json:"urls"`
+	}
+	if err := json.Unmarshal(inputBytes, &raw); err != nil {
+		return fmt.Errorf("error unmarshalling input JSON: %w", err)
+	}
+	duplicates, err := duplicateKeys(raw.Urls)
+	if err != nil {
+		return fmt.Errorf("error scanning urls for duplicates: %w", err)
+	}
+
+	for _, url := range duplicates {
+		if policy == "error" {
+			return fmt.Errorf("duplicate URL key '%s' in urls", url)
+		}
+		fmt.Fprintf(os.Stderr, "Warning: Duplicate URL key '%s' in urls. Using the last entry.\n", url)
+	}
+	return nil
+}
+
+// duplicateKeys returns the keys that occur more than once in a JSON object, in
+// order of their first repetition.
+func duplicateKeys(object json.RawMessage) ([]string, error) {
+	if len(object) == 0 {
+		return nil, nil
+	}
+	dec := json.NewDecoder(bytes.NewReader(object))
+	if _, err := dec.Token(); err != nil { // Opening '{'
+		return nil, err
+	}
+
+	seen := make(map[string]int)
+	var duplicates []string
+	for dec.More() {
+		tok, err := dec.Token()
+		if err != nil {
+			return nil, err
+		}
+		key, _ := tok.(string)
+		seen[key]++
+		if seen[key] == 2 {
+			duplicates = append(duplicates, key)
+		}
+
+		// Skip over the value
+		var value json.RawMessage
+		if err := dec.Decode(&value); err != nil {
+			return nil, err
+		}
+	}
+	return duplicates, nil
+}
+
+// unmarshalInput decodes and checks the input JSON as directed by opts.
+func unmarshalInput(inputBytes []byte, opts Options) (InputJson, error) {
+	var input InputJson
+	var err error
+	if opts.LenientUrls {
+		input, err = unmarshalInputLenient(inputBytes)
+	} else {
+		err = json.Unmarshal(inputBytes, &input)
+	}
+	if err != nil {
+		// Return an error instead of exiting
+		return InputJson{}, fmt.Errorf("error unmarshalling input JSON: %w", err)
+	}
+
+	if err := checkDuplicateUrls(inputBytes, input.OnDuplicateUrl); err != nil {
+		return InputJson{}, err
+	}
+	return input, nil
+}
+
+// compiledSelector is a selector ready for evaluation.
+type compiledSelector struct {
+	Selector
+	path         *xmlpath.Path
+	fallbacks    []*xmlpath.Path
+	when         *xmlpath.Path
+	concat       []*xmlpath.Path
+	attributes   []*xmlpath.Path
+	extractRegex *regexp.Regexp
+	transform    []transformOp
+	expectType   func(string) bool
+}
+
+// compileSelector compiles a selector's XPath, lowercasing element names to match
+// the parsed tree if normalizeTagCase is set, and validates its settings.
+func compileSelector(sel Selector, normalizeTagCase bool) (*compiledSelector, error) {
+	compileStr := sel.Xpath
+	if normalizeTagCase {
+		compileStr = lowercaseXpathNames(sel.Xpath)
+	}
+	path, err := xmlpath.Compile(compileStr)
+	if err != nil {
+		return nil, err
+	}
+
+	fallbacks := make([]*xmlpath.Path, 0, len(sel.Fallbacks))
+	for _, fallbackStr := range sel.Fallbacks {
+		if normalizeTagCase {
+			fallbackStr = lowercaseXpathNames(fallbackStr)
+		}
+		fallback, err := xmlpath.Compile(fallbackStr)
+		if err != nil {
+			return nil, fmt.Errorf("fallback '%s': %w", fallbackStr, err)
+		}
+		fallbacks = append(fallbacks, fallback)
+	}
+
+	concat := make([]*xmlpath.Path, 0, len(sel.Concat))
+	for _, concatStr := range sel.Concat {
+		if normalizeTagCase {
+			concatStr = lowercaseXpathNames(concatStr)
+		}
+		part, err := xmlpath.Compile(concatStr)
+		if err != nil {
+			return nil, fmt.Errorf("concat '%s': %w", concatStr, err)
+		}
+		concat = append(concat, part)
+	}
+
+	var when *xmlpath.Path
+	if sel.When != "" {
+		whenStr := sel.When
+		if normalizeTagCase {
+			whenStr = lowercaseXpathNames(whenStr)
+		}
+		when, err = xmlpath.Compile(whenStr)
+		if err != nil {
+			return nil, fmt.Errorf("when '%s': %w", sel.When, err)
+		}
+	}
+
+	attributes := make([]*xmlpath.Path, 0, len(sel.Attributes))
+	for _, name := range sel.Attributes {
+		attribute, err := xmlpath.Compile("@" + name)
+		if err != nil {
+			return nil, fmt.Errorf("attribute '%s': %w", name, err)
+		}
+		attributes = append(attributes, attribute)
+	}
+
+	if sel.UrlPolicy != "" && sel.UrlPolicy != urlPolicyAll && sel.UrlPolicy != urlPolicyFirstMatch {
+		return nil, fmt.Errorf("invalid url_policy %q: expected %q or %q", sel.UrlPolicy, urlPolicyAll, urlPolicyFirstMatch)
+	}
+
+	var extractRegex *regexp.Regexp
+	if sel.ExtractRegex != "" {
+		extractRegex, err = regexp.Compile(sel.ExtractRegex)
+		if err != nil {
+			return nil, fmt.Errorf("extract_regex: %w", err)
+		}
+	}
+
+	transform, err := parseTransform(sel.Transform)
+	if err != nil {
+		return nil, err
+	}
+
+	var expectType func(string) bool
+	if sel.ExpectType != "" {
+		expectType, err = valueTypeValidator(sel.ExpectType)
+		if err != nil {
+			return nil, err
+		}
+	}
+	if sel.ExpectDistinctCount < 0 {
+		return nil, fmt.Errorf("invalid expect_distinct_count %d: expected a positive count", sel.ExpectDistinctCount)
+	}
+	if sel.MinSuccessCount < 0 {
+		return nil, fmt.Errorf("invalid min_success_count %d: expected a positive count", sel.MinSuccessCount)
+	}
+	if sel.MinSuccessRatio < 0 || sel.MinSuccessRatio > 1 {
+		return nil, fmt.Errorf("invalid min_success_ratio %g: expected a ratio between 0 and 1", sel.MinSuccessRatio)
+	}
+	return &compiledSelector{
+		Selector:     sel,
+		path:         path,
+		fallbacks:    fallbacks,
+		when:         when,
+		concat:       concat,
+		attributes:   attributes,
+		extractRegex: extractRegex,
+		transform:    transform,
+		expectType:   expectType,
+	}, nil
+}
+
+// evaluate returns the post-processed value from the first of the selector's XPath
+// and its fallbacks to match root, and whether any of them matched. Each evaluation
+// is bounded by timeout if it is positive; reading attributes is not. Nothing is
+// evaluated if the selector's when precondition does not match root.
+func (c *compiledSelector) evaluate(root *xmlpath.Node, timeout time.Duration) (string, bool, error) {
+	value, _, ok, err := c.evaluateFrom(root, timeout)
+	if errors.Is(err, errPreconditionNotMet) {
+		return "", false, nil
+	}
+	return value, ok, err
+}
+
+// countDistinct returns the number of distinct values among the nodes path
+// matches from root.
+func countDistinct(path *xmlpath.Path, root *xmlpath.Node) int {
+	values := make(map[string]bool)
+	for iter := path.Iter(root); iter.Next(); {
+		values[iter.Node().String()] = true
+	}
+	return len(values)
+}
+
+// errPreconditionNotMet is returned by evaluateFrom when the selector's when
+// precondition does not match root, so the pair is skipped rather than unmatched.
+var errPreconditionNotMet = errors.New("when precondition not met")
+
+// evaluateFrom is evaluate, also returning the fallback XPath that supplied the
+// value, or "" if the selector's own XPath did.
+func (c *compiledSelector) evaluateFrom(root *xmlpath.Node, timeout time.Duration) (string, string, bool, error) {
+	if c.when != nil && !c.when.Exists(root) {
+		return "", "", false, errPreconditionNotMet
+	}
+	paths := append([]*xmlpath.Path{c.path}, c.fallbacks...)
+	for i, path := range paths {
+		var value string
+		var ok bool
+		var err error
+		if len(c.attributes) > 0 {
+			value, ok, err = c.joinAttributes(path, root)
+		} else {
+			var resultBytes []byte
+			resultBytes, ok, err = evaluateWithTimeout(path, root, timeout)
+			value = string(resultBytes)
+		}
+		if err == nil && ok {
+			value, ok, err = c.concatenate(value, root, timeout)
+		}
+		if err != nil {
+			return "", "", false, err
+		}
+		if ok {
+			value, matched := c.postProcess(value)
+			if matched && c.expectType != nil && !c.expectType(value) {
+				return "", "", false, fmt.Errorf("value %q is not a valid %s", value, c.ExpectType)
+			}
+			if matched && c.ExpectDistinctCount > 0 {
+				if distinct := countDistinct(path, root); distinct != c.ExpectDistinctCount {
+					return "", "", false, fmt.Errorf("matched %d distinct values, expected %d", distinct, c.ExpectDistinctCount)
+				}
+			}
+			fallback := ""
+			if i > 0 {
+				fallback = c.Fallbacks[i-1]
+			}
+			return value, fallback, matched, nil
+		}
+	}
+	return "", "", false, nil
+}
+
+// concatenate appends the values of the selector's concat XPaths to value, joined
+// by its separator. It reports false if any of them matches nothing.
+func (c *compiledSelector) concatenate(value string, root *xmlpath.Node, timeout time.Duration) (string, bool, error) {
+	if len(c.concat) == 0 {
+		return value, true, nil
+	}
+	parts := []string{value}
+	for _, path := range c.concat {
+		resultBytes, ok, err := evaluateWithTimeout(path, root, timeout)
+		if err != nil || !ok {
+			return "", false, err
+		}
+		parts = append(parts, string(resultBytes))
+	}
+
+	separator := c.ConcatSeparator
+	if separator == "" {
+		separator = " "
+	}
+	return strings.Join(parts, separator), true, nil
+}
+
+// joinAttributes returns the selector's attributes of the first element path
+// selects from root, joined by its separator. It fails if any is missing.
+func (c *compiledSelector) joinAttributes(path *xmlpath.Path, root *xmlpath.Node) (string, bool, error) {
+	iter := path.Iter(root)
+	if !iter.Next() {
+		return "", false, nil
+	}
+	values := make([]string, len(c.attributes))
+	for i, attribute := range c.attributes {
+		value, ok := attribute.String(iter.Node())
+		if !ok {
+			return "", false, fmt.Errorf("matched element has no attribute '%s'", c.Attributes[i])
+		}
+		values[i] = value
+	}
+
+	separator := c.AttributeSeparator
+	if separator == "" {
+		separator = " "
+	}
+	return strings.Join(values, separator), true, nil
+}
+
+// postProcess applies the selector's settings to an extracted value. It reports
+// false if extract_regex does not match, in which case there is no value.
+func (c *compiledSelector) postProcess(value string) (string, bool) {
+	if c.extractRegex != nil {
+		m := c.extractRegex.FindStringSubmatch(value)
+		if m == nil {
+			return "", false
+		}
+		// Use the first capture group, or the whole match if there are none
+		value = m[0]
+		if len(m) > 1 {
+			value = m[1]
+		}
+	}
+	for _, op := range c.transform {
+		value = op(value)
+	}
+	return value, true
+}
+
+// errEvalTimeout is returned when an XPath evaluation exceeds eval_timeout_ms.
+var errEvalTimeout = errors.New("evaluation timed out")
+
+// evaluateBytes evaluates a compiled XPath; tests replace it to simulate slow selectors.
+var evaluateBytes = func(path *xmlpath.Path, root *xmlpath.Node) ([]byte, bool) {
+	return path.Bytes(root)
+}
+
+// evaluateWithTimeout evaluates path against root, giving up after timeout if it is
+// positive. xmlpath evaluation cannot be interrupted, so a timed-out evaluation
+// finishes in the background and its result is discarded.
+func evaluateWithTimeout(path *xmlpath.Path, root *xmlpath.Node, timeout time.Duration) ([]byte, bool, error) {
+	if timeout <= 0 {
+		resultBytes, ok := evaluateBytes(path, root)
+		return resultBytes, ok, nil
+	}
+
+	type result struct {
+		bytes []byte
+		ok    bool
+	}
+	done := make(chan result, 1) // Buffered so an abandoned evaluation can still finish
+	eval := evaluateBytes        // Read here; an abandoned goroutine must not race a test restoring it
+	go func() {
+		resultBytes, ok := eval(path, root)
+		done <- result{resultBytes, ok}
+	}()
+
+	timer := time.NewTimer(timeout)
+	defer timer.Stop()
+	select {
+	case r := <-done:
+		return r.bytes, r.ok, nil
+	case <-timer.C:
+		return nil, false, fmt.Errorf("%w after %v", errEvalTimeout, timeout)
+	}
+}
+
+// loadedPage is a URL's loaded content, with its parsed tree unless it is plain text.
+type loadedPage struct {
+	content string
+	root    *xmlpath.Node // Nil for content_type "text"
+	times   *urlTiming
+}
+
+// loadPage loads a URL's content and parses it: resolveContent, the invalid_encoding
+// policy, then for markup parse_mode and decode. Plain-text content is returned
+// unparsed. The parse is recorded in timing, which may be nil.
+func loadPage(ctx context.Context, url string, urlData UrlData, input InputJson, popts parseOptions, opts Options, timing *timingReport) (loadedPage, error) {
+	content, err := resolveContent(ctx, url, urlData, opts)
+	if err == nil {
+		content, err = checkEncoding(url, content, input.InvalidEncoding)
+	}
+	if err != nil {
+		return loadedPage{}, err
+	}
+
+	switch urlData.ContentType {
+	case "", contentTypeHTML:
+		// Parsed below
+	case contentTypeText:
+		return loadedPage{content: content}, nil
+	default:
+		return loadedPage{}, fmt.Errorf("unknown content_type '%s'", urlData.ContentType)
+	}
+	if content, err = applyParseMode(content, urlData.ParseMode); err != nil {
+		return loadedPage{}, err
+	}
+
+	// Decode the content *once* per URL
+	parseStart := timing.start()
+	root, err := decode(strings.NewReader(content), popts)
+	times := timing.addURL(url, parseStart)
+	if err != nil {
+		return loadedPage{}, fmt.Errorf("parsing: %w", err)
+	}
+	// xmlpath.ParseDecoder usually returns EOF for empty input, caught above.
+	// This check handles edge cases where parsing succeeds but yields no root.
+	if root == nil {
+		return loadedPage{}, errors.New("parsed content resulted in nil root node")
+	}
+	return loadedPage{content: content, root: root, times: times}, nil
+}
+
+// parsedPage is a URL's content parsed once for repeated evaluation.
+type parsedPage struct {
+	url  string
+	root *xmlpath.Node
+}
+
+// parsePages loads and parses the content of every URL in the input, sorted by URL.
+// URLs that fail to load or parse are logged and left out, as are plain-text URLs,
+// which have no tree.
+func parsePages(ctx context.Context, input InputJson, opts Options) []parsedPage {
+	popts := parseOptionsFor(input)
+	pages := make([]parsedPage, 0, len(input.Urls))
+	for url, urlData := range input.Urls {
+		page, err := loadPage(ctx, url, urlData, input, popts, opts, nil)
+		if err != nil {
+			fmt.Fprintf(os.Stderr, "Warning: Failed to load content for URL '%s': %v. Skipping this URL.\n", url, err)
+			continue
+		}
+		if page.root == nil {
+			continue
+		}
+		pages = append(pages, parsedPage{url: url, root: page.root})
+	}
+	sort.Slice(pages, func(i, j int) bool { return pages[i].url < pages[j].url })
+	return pages
 }
 
 // --- Processing Logic ---
 
 // processInput takes raw input bytes, processes them, and returns the result map or an error.
 func processInput(inputBytes []byte) (OutputJson, error) {
+	return processInputWithOptions(context.Background(), inputBytes, Options{})
+}
+
+// processInputWithOptions is processInput with command-line options applied.
+// If ctx is done before all URLs are processed, it returns the results collected
+// so far together with an error wrapping the context's cause.
+func processInputWithOptions(ctx context.Context, inputBytes []byte, opts Options) (OutputJson, error) {
 	// 1. Deserialize input
-	var input InputJson
-	err := json.Unmarshal(inputBytes, &input)
+	input, err := unmarshalInput(inputBytes, opts)
 	if err != nil {
-		// Return an error instead of exiting
-		return nil, fmt.Errorf("error unmarshalling input JSON: %w", err)
+		return nil, err
+	}
+	return processInputJson(ctx, input, opts)
+}
+
+// processInputJson processes an already decoded input.
+func processInputJson(ctx context.Context, input InputJson, opts Options) (OutputJson, error) {
+	run, err := runInput(ctx, input, opts)
+	return run.output, err
+}
+
+// runResult is a run's output together with the URLs it reached and why pairs
+// without a value have none, for the formats that report every pair.
+type runResult struct {
+	output OutputJson
+	// selectors are the selectors the run used, after tag filtering and dedup.
+	selectors []Selector
+	// urls are the URLs the run reached, sorted; a run stopped early leaves out the rest.
+	urls []string
+	// selectorErrors, urlErrors and pairErrors hold the failures of selectors
+	// that did not compile, URLs that did not load and single evaluations.
+	// lineErrors replace selectorErrors on textUrls, where selectors are line regexes.
+	selectorErrors map[string]string
+	lineErrors     map[string]string
+	urlErrors      map[string]string
+	pairErrors     map[string]map[string]string
+	textUrls       map[string]bool
+	// skipped marks pairs not evaluated because of url_policy, exclude_urls or when.
+	skipped map[string]map[string]bool
+	// histograms, if --match-histogram is on, tally the nodes matched by each
+	// evaluated pair.
+	histograms matchHistograms
+}
+
+func newRunResult() runResult {
+	return runResult{
+		output:         make(OutputJson),
+		urls:           []string{},
+		selectorErrors: make(map[string]string),
+		lineErrors:     make(map[string]string),
+		urlErrors:      make(map[string]string),
+		pairErrors:     make(map[string]map[string]string),
+		skipped:        make(map[string]map[string]bool),
+		textUrls:       make(map[string]bool),
+	}
+}
+
+// pairError returns the error that kept xpathStr from producing a value on url,
+// or "" if there was none.
+func (r runResult) pairError(xpathStr, url string) string {
+	if err, ok := r.urlErrors[url]; ok {
+		return err
+	}
+	if r.textUrls[url] {
+		return r.lineErrors[xpathStr]
+	}
+	if err, ok := r.selectorErrors[xpathStr]; ok {
+		return err
+	}
+	return r.pairErrors[xpathStr][url]
+}
+
+// isSkipped reports whether the pair was deliberately not evaluated.
+func (r runResult) isSkipped(xpathStr, url string) bool {
+	return r.skipped[xpathStr][url]
+}
+
+// pairFailed records why evaluating the pair failed.
+func (r runResult) pairFailed(xpathStr, url, err string) {
+	if r.pairErrors[xpathStr] == nil {
+		r.pairErrors[xpathStr] = make(map[string]string)
+	}
+	r.pairErrors[xpathStr][url] = err
+}
+
+// skip records that the pair was deliberately not evaluated.
+func (r runResult) skip(xpathStr, url string) {
+	if r.skipped[xpathStr] == nil {
+		r.skipped[xpathStr] = make(map[string]bool)
+	}
+	r.skipped[xpathStr][url] = true
+}
+
+// runInput processes an already decoded input, recording for every pair whether
+// and why it produced a value.
+func runInput(ctx context.Context, input InputJson, opts Options) (runResult, error) {
+	// Timing is only collected when a report was requested
+	var timing *timingReport
+	if opts.TimingReport != nil {
+		timing = newTimingReport()
+		defer timing.write(opts.TimingReport)
+	}
+	if opts.MemoryReport != nil {
+		profile := startMemoryProfile()
+		defer func() { profile.stop().write(opts.MemoryReport) }()
 	}
 
 	// 2. Initialize Output and Compile XPaths
-	output := make(OutputJson)
-	compiledPaths := make(map[string]*xmlpath.Path) // Store compiled XPaths
+	run := newRunResult()
+	output := run.output
+	compiledPaths := make(map[string]*compiledSelector) // Store compiled selectors
 
-	for _, xpathStr := range input.Xpaths {
+	selectors := filterByTags(input.Xpaths, opts)
+	if input.CanonicalizeXpaths {
+		selectors = canonicalizeSelectors(selectors)
+	}
+
+	selectors = dedupXpaths(selectors)
+	run.selectors = selectors
+	var lint *selectorLint
+	if opts.LintReport != nil {
+		lint = newSelectorLint(selectors)
+		defer lint.write(opts.LintReport)
+	}
+	// An XPath that fails to compile only matters for HTML URLs; text URLs use the
+	// selector as a line regex instead
+	hasTree, hasText := hasTreeContent(input), hasTextContent(input)
+	for _, sel := range selectors {
 		// Initialize the inner map for this XPath in the output
-		output[xpathStr] = make(map[string]string)
+		output[sel.Xpath] = make(map[string]string)
 
-		// Compile XPath expression
-		path, err := xmlpath.Compile(xpathStr)
+		// Compile the XPath and the selector's settings
+		compiled, err := compileSelector(sel, input.NormalizeTagCase)
 		if err != nil {
 			// Log warning, but don't stop processing other paths/URLs
-			fmt.Fprintf(os.Stderr, "Warning: Failed to compile XPath '%s': %v. Skipping this XPath for all URLs.\n", xpathStr, err)
+			if hasTree && hasText {
+				fmt.Fprintf(os.Stderr, "Warning: Failed to compile selector %s: %v. Skipping this XPath for HTML URLs.\n", sel.label(), err)
+			} else if hasTree {
+				fmt.Fprintf(os.Stderr, "Warning: Failed to compile selector %s: %v. Skipping this XPath for all URLs.\n", sel.label(), err)
+			}
 			// We skip adding it to compiledPaths, so it won't be processed.
+			run.selectorErrors[sel.Xpath] = err.Error()
 		} else {
-			compiledPaths[xpathStr] = path
+			compiledPaths[sel.Xpath] = compiled
 		}
 	}
 
-	// 3. Process URLs and Apply Compiled XPaths
-	for url, urlData := range input.Urls {
-		// Create a reader for the HTML/XML content string
-		contentReader := strings.NewReader(urlData.Content)
+	if opts.MatchHistogram != nil {
+		run.histograms = newMatchHistograms(compiledPaths)
+		defer run.histograms.write(opts.MatchHistogram)
+	}
 
-		// Decode the content *once* per URL
-		root, err := decode(contentReader)
+	excludeUrls := compileExcludeUrls(selectors)
+	// skipPair reports whether the run leaves the pair unevaluated whatever the page
+	// holds, because of url_policy first_match or exclude_urls, recording it if so
+	skipPair := func(xpathStr, url string) bool {
+		compiled := compiledPaths[xpathStr]
+		// A first_match selector stops once some URL has produced a value
+		if (compiled != nil && compiled.UrlPolicy == urlPolicyFirstMatch && len(output[xpathStr]) > 0) ||
+			excludedUrl(excludeUrls[xpathStr], url) {
+			run.skip(xpathStr, url)
+			return true
+		}
+		return false
+	}
+
+	// Resume from a checkpoint, restoring the results of URLs it completed
+	var resume *checkpoint
+	if opts.Checkpoint != "" {
+		fingerprint, err := runFingerprint(input, selectors)
 		if err != nil {
-			// Log warning and skip this URL entirely if parsing fails
-			fmt.Fprintf(os.Stderr, "Warning: Failed to parse content for URL '%s': %v. Skipping this URL.\n", url, err)
-			continue // Skip to the next URL
+			return runResult{}, err
+		}
+		if resume, err = loadCheckpoint(opts.Checkpoint, fingerprint); err != nil {
+			return runResult{}, err
 		}
+		resume.restore(run)
+	}
+
+	// Plain-text URLs use the selectors as line regexes instead
+	var lineRegexes map[string]*regexp.Regexp
+	if hasText {
+		lineRegexes, run.lineErrors = compileLineRegexes(selectors)
+	}
 
-		// If root is nil even after successful decode (e.g., empty valid XML), skip URL.
-		// xmlpath.ParseDecoder usually returns EOF for empty input, caught above.
-		// This check handles edge cases where parsing succeeds but yields no root.
-		if root == nil {
-			fmt.Fprintf(os.Stderr, "Warning: Parsed content for URL '%s' resulted in nil root node. Skipping this URL.\n", url)
-			continue // Skip to the next URL
+	// 3. Process URLs and Apply Compiled XPaths
+	popts := parseOptionsFor(input)
+	evalTimeout := time.Duration(input.EvalTimeoutMs) * time.Millisecond
+	// URLs are visited in sorted order so that url_policy "first_match" is deterministic
+	urls := make([]string, 0, len(input.Urls))
+	for url := range input.Urls {
+		urls = append(urls, url)
+	}
+	sort.Strings(urls)
+
+	processed := 0
+	for _, url := range urls {
+		urlData := input.Urls[url]
+		// Stop between URLs once the run is cancelled, keeping what completed
+		if ctx.Err() != nil {
+			resume.save(run)
+			return run, fmt.Errorf("stopped after %d of %d URLs: %w", processed, len(input.Urls), context.Cause(ctx))
 		}
+		processed++
+		run.urls = append(run.urls, url)
+		if resume.completed(url) {
+			continue
+		}
+		resume.start(url, run)
+
+		page, err := loadPage(ctx, url, urlData, input, popts, opts, timing)
+		if err != nil {
+			// Log warning and skip this URL entirely if it cannot be loaded or parsed
+			fmt.Fprintf(os.Stderr, "Warning: Failed to load content for URL '%s': %v. Skipping this URL.\n", url, err)
+			run.urlErrors[url] = err.Error()
+			for xpathStr := range output {
+				if !skipPair(xpathStr, url) {
+					run.histograms.add(xpathStr, 0) // Matched nothing
+				}
+			}
+			continue
+		}
+		if page.root == nil {
+			// Plain text: report the first line each regex matches
+			run.textUrls[url] = true
+			for xpathStr := range output {
+				if skipPair(xpathStr, url) {
+					continue
+				}
+				run.histograms.add(xpathStr, 0) // Text has no nodes
+				if re, ok := lineRegexes[xpathStr]; ok {
+					if line, ok := firstMatchingLine(re, page.content); ok {
+						output[xpathStr][url] = line
+					}
+				}
+			}
+			continue
+		}
+		root, urlTimes := page.root, page.times
+		lint.checkPage(url, root, compiledPaths)
 
 		// Apply each valid, compiled XPath to this URL's content
-		for xpathStr, path := range compiledPaths {
+		for xpathStr, compiled := range compiledPaths {
+			if skipPair(xpathStr, url) {
+				continue
+			}
+
 			// Evaluate the XPath on the parsed root
-			resultBytes, ok := path.Bytes(root)
-			// Only add the entry if the XPath matched and returned bytes
+			evalStart := timing.start()
+			value, fallback, ok, err := compiled.evaluateFrom(root, evalTimeout)
+			urlTimes.addSelector(xpathStr, evalStart)
+			if errors.Is(err, errPreconditionNotMet) {
+				run.skip(xpathStr, url)
+				continue
+			}
+			if run.histograms != nil {
+				run.histograms.add(xpathStr, countMatches(compiled.path, root))
+			}
+			if err != nil {
+				fmt.Fprintf(os.Stderr, "Warning: Evaluating XPath %s for URL '%s': %v. Omitting this result.\n", compiled.label(), url, err)
+				run.pairFailed(xpathStr, url, err.Error())
+				continue
+			}
+			if ok && fallback != "" {
+				fmt.Fprintf(os.Stderr, "Note: XPath %s matched nothing for URL '%s'. Using the value of fallback '%s'.\n", compiled.label(), url, fallback)
+			}
+			// Only add the entry if the XPath (or a fallback) matched and returned bytes
 			if ok {
-				output[xpathStr][url] = string(resultBytes)
+				if compiled.ResolveUrls {
+					base := urlData.BaseUrl
+					if base == "" {
+						base = url
+					}
+					value = resolveUrl(base, value)
+				}
+				output[xpathStr][url] = value
 			}
 			// If 'ok' is false (no match or non-byte result), do nothing - omit the entry.
 		}
 	}
 
-	return output, nil // Return the populated map and nil error if successful so far
+	resume.save(run)
+	return run, nil // Return the populated results and nil error if successful so far
+}
+
+// marshalOutput serializes v as JSON, indented for readability unless compact is set.
+func marshalOutput(v interface{}, compact bool) ([]byte, error) {
+	if compact {
+		return json.Marshal(v)
+	}
+	return json.MarshalIndent(v, "", "  ")
 }
 
 // --- Main Function ---
 
 func main() {
-	// 1. Read stdin
-	inputBytes, err := io.ReadAll(os.Stdin)
+	var opts Options
+	flag.StringVar(&opts.RendererURL, "renderer-url", "", "headless browser endpoint used for URLs with \"render\": true")
+	flag.BoolVar(&opts.LenientUrls, "lenient-urls", false, "skip malformed \"urls\" entries instead of rejecting the input")
+	flag.StringVar(&opts.Checkpoint, "checkpoint", "", "file recording completed URLs and results; a rerun with the same input resumes from it")
+	flag.StringVar(&opts.ContentDir, "content-dir", "", "directory of <sha1(url)>.html fixtures for URLs without inline content")
+	flag.Var((*stringList)(&opts.Tags), "tag", "only run selectors with this tag; repeatable")
+	flag.StringVar(&opts.TagMode, "tag-mode", tagModeAny, "how repeated --tag values combine: \"any\" (OR) or \"all\" (AND)")
+	flag.BoolVar(&opts.IncludeUntagged, "include-untagged", false, "also run untagged selectors when filtering by --tag")
+	timingReport := flag.Bool("timing-report", false, "print a per-URL and per-selector timing breakdown to stderr")
+	profileMemory := flag.Bool("profile-memory", false, "print the peak heap usage and bytes allocated by processing to stderr")
+	flag.StringVar(&opts.Format, "format", formatJson, "output format: \"json\" (map of XPath to URL to value), \"records\" (flat array, one entry per evaluated selector and URL), \"by-url\" (matched and unmatched selectors per URL), \"tap\" (Test Anything Protocol) or \"pretty-term\" (per-selector report, colored when stdout is a terminal)")
+	flag.BoolVar(&opts.WithProvenance, "with-provenance", false, "wrap results with the input SHA-256 and resolved configuration")
+	flag.BoolVar(&opts.WithRunMeta, "with-run-meta", false, "wrap output with a generated run id and RFC 3339 start and finish times")
+	flag.BoolVar(&opts.Compact, "compact", false, "emit single-line JSON instead of indented output")
+	maxRuntime := flag.Float64("max-runtime", 0, "overall time budget in seconds; on expiry partial results are printed (0 = unlimited)")
+	var inputFiles stringList
+	flag.Var(&inputFiles, "input", "input JSON file; repeat to merge several files in order (default: read stdin)")
+	onConflict := flag.String("on-conflict", onConflictOverride, "policy for a selector or URL defined in several --input files: \"override\" (later file wins) or \"error\"")
+	repl := flag.Bool("repl", false, "load --input once, then evaluate XPaths read line by line from stdin")
+	generate := flag.Bool("generate-fixture", false, "print a synthetic benchmark input sized by --urls, --selectors and --dom-size, then exit")
+	fixtureUrls := flag.Int("urls", 100, "number of URLs in a generated fixture")
+	fixtureSelectors := flag.Int("selectors", 10, "number of selectors in a generated fixture")
+	fixtureDomSize := flag.Int("dom-size", 100, "number of items per page in a generated fixture")
+	lint := flag.Bool("lint-selectors", false, "after processing, warn about selectors that look too broad")
+	histogram := flag.Bool("match-histogram", false, "after processing, print to stderr how many URLs each selector matched 0, 1, 2-5 and 6+ nodes on")
+	jsonLines := flag.Bool("json-lines", false, "read one {url, content, xpaths} record per stdin line and write one result line each")
+	compareWith := flag.String("compare-with", "", "input JSON file whose content is run through the same selectors; prints per-pair value differences")
+	dumpConfig := flag.Bool("dump-config", false, "print the resolved configuration as JSON and exit")
+	template := flag.String("template", "", "print one line per evaluated selector and URL using {status}, {url}, {selector}, {actual} and {error} placeholders instead of JSON")
+	requireMatches := flag.Bool("require-matches", false, "exit nonzero if any selector matched nothing on every URL, listing those selectors")
+	flag.Parse()
+	if *timingReport {
+		opts.TimingReport = os.Stderr
+	}
+	if *profileMemory {
+		opts.MemoryReport = os.Stderr
+	}
+	if *lint {
+		opts.LintReport = os.Stderr
+	}
+	if *histogram {
+		opts.MatchHistogram = os.Stderr
+	}
+	opts.MaxRuntime = time.Duration(*maxRuntime * float64(time.Second))
+	if err := validateFormat(opts.Format); err != nil {
+		fatalf("Error: %v\n", err)
+	}
+	if opts.TagMode != tagModeAny && opts.TagMode != tagModeAll {
+		fatalf("Error: invalid --tag-mode %q: expected %q or %q\n", opts.TagMode, tagModeAny, tagModeAll)
+	}
+	if err := validateTemplate(*template); err != nil {
+		fatalf("Error: %v\n", err)
+	}
+
+	if *generate {
+		if *fixtureUrls < 0 || *fixtureSelectors < 0 {
+			fatalf("Error: --urls and --selectors must not be negative\n")
+		}
+		fixtureJsonBytes, err := marshalOutput(generateFixture(*fixtureUrls, *fixtureSelectors, *fixtureDomSize), opts.Compact)
+		if err != nil {
+			fatalf("Error marshalling fixture JSON: %v\n", err)
+		}
+		fmt.Println(string(fixtureJsonBytes))
+		return
+	}
+
+	if *repl {
+		if len(inputFiles) == 0 {
+			fatalf("Error: --repl reads XPaths from stdin, so the input must be given with --input\n")
+		}
+		inputBytes, err := readInputs(nil, inputFiles, *onConflict, opts)
+		if err != nil {
+			fatalf("Error reading input: %v\n", err)
+		}
+		if err := runRepl(inputBytes, opts, os.Stdin, os.Stdout); err != nil {
+			fatalf("Error: %v\n", err)
+		}
+		return
+	}
+
+	if *jsonLines {
+		if opts.Checkpoint != "" {
+			fatalf("Error: --checkpoint cannot be used with --json-lines, which runs each line separately\n")
+		}
+		if err := runJsonLines(context.Background(), os.Stdin, os.Stdout, opts); err != nil {
+			fatalf("Error: %v\n", err)
+		}
+		return
+	}
+
+	// 1. Read stdin, or the merged --input files
+	inputBytes, err := readInputs(os.Stdin, inputFiles, *onConflict, opts)
 	if err != nil {
-		fatalf("Error reading stdin: %v\n", err) // Use fatalf for I/O errors in main
+		fatalf("Error reading input: %v\n", err) // Use fatalf for I/O errors in main
+	}
+
+	if *dumpConfig {
+		config, err := resolveConfig(inputBytes, opts)
+		if err != nil {
+			fatalf("Error resolving configuration: %v\n", err)
+		}
+		configJsonBytes, err := json.MarshalIndent(config, "", "  ")
+		if err != nil {
+			fatalf("Error marshalling configuration JSON: %v\n", err)
+		}
+		fmt.Println(string(configJsonBytes))
+		return
+	}
+
+	// The first Ctrl-C stops the run between URLs so partial results are printed
+	signals := make(chan os.Signal, 2)
+	signal.Notify(signals, os.Interrupt)
+	ctx, stopInterrupts := cancelOnInterrupt(context.Background(), signals)
+	defer stopInterrupts()
+	if opts.MaxRuntime > 0 {
+		var cancel context.CancelFunc
+		ctx, cancel = context.WithTimeoutCause(ctx, opts.MaxRuntime, errRuntimeExceeded)
+		defer cancel()
+	}
+
+	if *compareWith != "" {
+		if opts.Checkpoint != "" {
+			fatalf("Error: --checkpoint cannot be used with --compare-with, which runs each input separately\n")
+		}
+		otherBytes, err := os.ReadFile(*compareWith)
+		if err != nil {
+			fatalf("Error reading compare-with input: %v\n", err)
+		}
+		comparison, err := compareInputs(ctx, inputBytes, otherBytes, opts)
+		if err != nil {
+			fatalf("Error comparing inputs: %v\n", err)
+		}
+		comparisonJsonBytes, err := marshalOutput(comparison, opts.Compact)
+		if err != nil {
+			fatalf("Error marshalling comparison JSON: %v\n", err)
+		}
+		fmt.Println(string(comparisonJsonBytes))
+		return
 	}
 
 	// 2. Process Input using the dedicated function
-	output, err := processInput(inputBytes)
+	startedAt := time.Now()
+	input, err := unmarshalInput(inputBytes, opts)
 	if err != nil {
-		// Handle fatal errors from processing (e.g., JSON parsing)
 		fatalf("Error processing input: %v\n", err)
 	}
+	run, runErr := runInput(ctx, input, opts)
+	finishedAt := time.Now()
+	if runErr != nil && run.output == nil {
+		// Handle fatal errors from processing (e.g., a checkpoint that does not match)
+		fatalf("Error processing input: %v\n", runErr)
+	}
 
-	// 3. Serialize output
-	outputJsonBytes, err := json.MarshalIndent(output, "", "  ") // Use indent for readability
-	if err != nil {
-		fatalf("Error marshalling output JSON: %v\n", err) // Use fatalf for marshalling errors
+	if *template != "" {
+		// 3-4. Print one templated line per evaluated pair instead of JSON
+		for _, r := range toRecords(run) {
+			fmt.Println(renderTemplate(*template, r))
+		}
+	} else if opts.Format == formatTap {
+		// 3-4. TAP is plain text, so it is printed rather than serialized
+		fmt.Print(toTap(run))
+	} else if opts.Format == formatPretty {
+		// 3-4. The terminal report is plain text too
+		fmt.Print(toPrettyTerm(run, stdoutIsTerminal()))
+	} else {
+		// 3. Serialize output, wrapped with provenance and run metadata if requested
+		results, err := shapeOutput(run, opts.Format)
+		if err != nil {
+			fatalf("Error formatting output: %v\n", err)
+		}
+		if opts.WithProvenance {
+			config, err := resolveConfig(inputBytes, opts)
+			if err != nil {
+				fatalf("Error resolving configuration: %v\n", err)
+			}
+			results = withProvenance(inputBytes, config, results)
+		}
+		if opts.WithRunMeta {
+			runId, err := newRunId()
+			if err != nil {
+				fatalf("Error generating run id: %v\n", err)
+			}
+			results = withRunMeta(runId, startedAt, finishedAt, results)
+		}
+		outputJsonBytes, err := marshalOutput(results, opts.Compact)
+		if err != nil {
+			fatalf("Error marshalling output JSON: %v\n", err) // Use fatalf for marshalling errors
+		}
+
+		// 4. Print to stdout
+		fmt.Println(string(outputJsonBytes))
 	}
 
-	// 4. Print to stdout
-	fmt.Println(string(outputJsonBytes))
+	// A run stopped early still prints what completed, then fails
+	if errors.Is(runErr, errInterrupted) {
+		fmt.Fprintf(os.Stderr, "Error: %v. The output above is partial.\n", runErr)
+		os.Exit(exitInterrupted)
+	}
+	if runErr != nil {
+		fatalf("Error: %v. The output above is partial.\n", runErr)
+	}
+	if *requireMatches {
+		if err := checkRequiredMatches(run.output); err != nil {
+			fatalf("Error: %v\n", err)
+		}
+	}
+	if err := checkSuccessThresholds(run); err != nil {
+		fatalf("Error: %v\n", err)
+	}
 }
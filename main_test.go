@@ -1,9 +1,18 @@
 package main
 
 import (
+	"context"
 	"encoding/json" // Import encoding/json for test output formatting
-	"reflect"       // Import reflect package for DeepEqual
+	"errors"
+	"fmt"
+	"io"
+	"os"
+	"reflect" // Import reflect package for DeepEqual
+	"strings"
 	"testing"
+	"time"
+
+	"launchpad.net/xmlpath"
 )
 
 func TestProcessInput(t *testing.T) {
@@ -65,6 +74,27 @@ func TestProcessInput(t *testing.T) {
 	}
 }
 
+// captureStderr runs fn and returns everything it wrote to os.Stderr.
+func captureStderr(t *testing.T, fn func()) string {
+	t.Helper()
+	r, w, err := os.Pipe()
+	if err != nil {
+		t.Fatalf("Failed to create pipe: %v", err)
+	}
+	stderr := os.Stderr
+	os.Stderr = w
+	defer func() { os.Stderr = stderr }()
+
+	captured := make(chan string)
+	go func() {
+		data, _ := io.ReadAll(r)
+		captured <- string(data)
+	}()
+	fn()
+	w.Close()
+	return <-captured
+}
+
 // Test case for invalid input JSON
 func TestProcessInput_InvalidJson(t *testing.T) {
 	inputJsonBytes := []byte(`{invalid json`)
@@ -113,3 +143,699 @@ func TestProcessInput_InvalidXPath(t *testing.T) {
 		t.Errorf("Unexpected output for invalid XPath.\nExpected:\n%s\nGot:\n%s", string(expectedJson), string(actualJson))
 	}
 }
+
+// Test case for a selector listed more than once
+func TestProcessInput_DuplicateXPath(t *testing.T) {
+	// Count evaluations to confirm the duplicate is evaluated once
+	original := evaluateBytes
+	defer func() { evaluateBytes = original }()
+	evaluations := 0
+	evaluateBytes = func(path *xmlpath.Path, root *xmlpath.Node) ([]byte, bool) {
+		evaluations++
+		return original(path, root)
+	}
+
+	inputJsonBytes := []byte(`{
+		"xpaths": ["/html/body/p", "//title", "/html/body/p"],
+		"urls": {
+			"http://example.com": {
+				"content": "<html><head><title>Test Page</title></head><body><p>Hello</p></body></html>"
+			}
+		}
+	}`)
+
+	expectedOutput := OutputJson{
+		"/html/body/p": {
+			"http://example.com": "Hello",
+		},
+		"//title": {
+			"http://example.com": "Test Page",
+		},
+	}
+
+	actualOutput, err := processInput(inputJsonBytes)
+	if err != nil {
+		t.Fatalf("processInput returned an unexpected error: %v", err)
+	}
+
+	if !reflect.DeepEqual(expectedOutput, actualOutput) {
+		expectedJson, _ := json.MarshalIndent(expectedOutput, "", "  ")
+		actualJson, _ := json.MarshalIndent(actualOutput, "", "  ")
+		t.Errorf("Unexpected output for duplicate XPath.\nExpected:\n%s\nGot:\n%s", string(expectedJson), string(actualJson))
+	}
+	// One evaluation per distinct selector on the one URL
+	if evaluations != 2 {
+		t.Errorf("Expected the duplicate XPath to be evaluated once, got %d evaluations for 2 distinct selectors", evaluations)
+	}
+}
+
+// Test case for loading content from a fixture directory keyed by URL hash
+func TestProcessInput_ContentDir(t *testing.T) {
+	dir := t.TempDir()
+	fixture := "<html><body><p>From fixture</p></body></html>"
+	if err := os.WriteFile(fixturePath(dir, "http://fixture.com"), []byte(fixture), 0o644); err != nil {
+		t.Fatalf("Failed to write fixture: %v", err)
+	}
+
+	inputJsonBytes := []byte(`{
+		"xpaths": ["/html/body/p"],
+		"urls": {
+			"http://fixture.com": {},
+			"http://missing.com": {},
+			"http://inline.com": {
+				"content": "<html><body><p>Inline</p></body></html>"
+			}
+		}
+	}`)
+
+	expectedOutput := OutputJson{
+		"/html/body/p": {
+			"http://fixture.com": "From fixture",
+			// Inline content takes precedence over the fixture directory
+			"http://inline.com": "Inline",
+			// "http://missing.com" is omitted because it has no fixture file
+		},
+	}
+
+	actualOutput, err := processInputWithOptions(context.Background(), inputJsonBytes, Options{ContentDir: dir})
+	if err != nil {
+		t.Fatalf("processInputWithOptions returned an unexpected error: %v", err)
+	}
+
+	if !reflect.DeepEqual(expectedOutput, actualOutput) {
+		expectedJson, _ := json.MarshalIndent(expectedOutput, "", "  ")
+		actualJson, _ := json.MarshalIndent(actualOutput, "", "  ")
+		t.Errorf("Unexpected output for content directory.\nExpected:\n%s\nGot:\n%s", string(expectedJson), string(actualJson))
+	}
+}
+
+// Test case for a run that exceeds its time budget
+func TestProcessInput_MaxRuntime(t *testing.T) {
+	ctx, cancel := context.WithTimeoutCause(context.Background(), 100*time.Millisecond, errRuntimeExceeded)
+	defer cancel()
+
+	// The second URL is slow enough to outlast the budget
+	original := evaluateBytes
+	defer func() { evaluateBytes = original }()
+	calls := 0
+	evaluateBytes = func(path *xmlpath.Path, root *xmlpath.Node) ([]byte, bool) {
+		calls++
+		if calls == 2 {
+			select {
+			case <-ctx.Done():
+			case <-time.After(5 * time.Second):
+				t.Errorf("Expected the runtime budget to expire")
+			}
+		}
+		return original(path, root)
+	}
+
+	inputJsonBytes := []byte(`{
+		"xpaths": ["/html/body/p"],
+		"urls": {
+			"http://example.com/1": {"content": "<html><body><p>One</p></body></html>"},
+			"http://example.com/2": {"content": "<html><body><p>Two</p></body></html>"},
+			"http://example.com/3": {"content": "<html><body><p>Three</p></body></html>"}
+		}
+	}`)
+
+	actualOutput, err := processInputWithOptions(ctx, inputJsonBytes, Options{})
+	if !errors.Is(err, errRuntimeExceeded) {
+		t.Fatalf("Expected a runtime exceeded error, got %v", err)
+	}
+
+	// The URLs completed before and during the expiry are emitted; the rest are not started
+	expectedOutput := OutputJson{
+		"/html/body/p": {
+			"http://example.com/1": "One",
+			"http://example.com/2": "Two",
+		},
+	}
+	if !reflect.DeepEqual(expectedOutput, actualOutput) {
+		expectedJson, _ := json.MarshalIndent(expectedOutput, "", "  ")
+		actualJson, _ := json.MarshalIndent(actualOutput, "", "  ")
+		t.Errorf("Unexpected partial output.\nExpected:\n%s\nGot:\n%s", string(expectedJson), string(actualJson))
+	}
+}
+
+// Test case for case-insensitive element matching of custom tags
+func TestProcessInput_NormalizeTagCase(t *testing.T) {
+	content := `<doc><js-Literal data-Kind=\"x\">some data</js-Literal></doc>`
+	inputFor := func(normalize bool) []byte {
+		return []byte(fmt.Sprintf(`{
+			"xpaths": ["//js-literal/text()", "//js-LITERAL/@data-Kind"],
+			"urls": {"http://customtag.com": {"content": "%s"}},
+			"normalize_tag_case": %t
+		}`, content, normalize))
+	}
+
+	// Element matching stays case-sensitive by default
+	actualOutput, err := processInput(inputFor(false))
+	if err != nil {
+		t.Fatalf("processInput returned an unexpected error: %v", err)
+	}
+	if len(actualOutput["//js-literal/text()"]) != 0 {
+		t.Errorf("Expected no match without normalization, got %v", actualOutput)
+	}
+
+	expectedOutput := OutputJson{
+		"//js-literal/text()": {
+			"http://customtag.com": "some data",
+		},
+		// Attribute names are not lowercased, only element names
+		"//js-LITERAL/@data-Kind": {
+			"http://customtag.com": "x",
+		},
+	}
+
+	actualOutput, err = processInput(inputFor(true))
+	if err != nil {
+		t.Fatalf("processInput returned an unexpected error: %v", err)
+	}
+
+	if !reflect.DeepEqual(expectedOutput, actualOutput) {
+		expectedJson, _ := json.MarshalIndent(expectedOutput, "", "  ")
+		actualJson, _ := json.MarshalIndent(actualOutput, "", "  ")
+		t.Errorf("Unexpected output for normalized tag case.\nExpected:\n%s\nGot:\n%s", string(expectedJson), string(actualJson))
+	}
+}
+
+// Test case for compact versus pretty output serialization
+func TestMarshalOutput_Compact(t *testing.T) {
+	output := OutputJson{
+		"/html/body/p": {
+			"http://example.com": "Hello",
+			"http://other.com":   "World",
+		},
+		"//nonexistent": {},
+	}
+
+	compactJson, err := marshalOutput(output, true)
+	if err != nil {
+		t.Fatalf("marshalOutput returned an unexpected error: %v", err)
+	}
+	prettyJson, err := marshalOutput(output, false)
+	if err != nil {
+		t.Fatalf("marshalOutput returned an unexpected error: %v", err)
+	}
+
+	if strings.Contains(string(compactJson), "\n") {
+		t.Errorf("Expected compact output without newlines, got:\n%s", string(compactJson))
+	}
+
+	// Both forms must decode to the same value
+	var fromCompact, fromPretty OutputJson
+	if err := json.Unmarshal(compactJson, &fromCompact); err != nil {
+		t.Fatalf("Failed to parse compact output: %v", err)
+	}
+	if err := json.Unmarshal(prettyJson, &fromPretty); err != nil {
+		t.Fatalf("Failed to parse pretty output: %v", err)
+	}
+	if !reflect.DeepEqual(fromCompact, fromPretty) {
+		t.Errorf("Compact and pretty output differ.\nCompact: %s\nPretty: %s", string(compactJson), string(prettyJson))
+	}
+}
+
+// Test case for skipping malformed URL entries in lenient mode
+func TestProcessInput_LenientUrls(t *testing.T) {
+	inputJsonBytes := []byte(`{
+		"xpaths": ["/html/body/p"],
+		"urls": {
+			"http://good1.com": {
+				"content": "<html><body><p>One</p></body></html>"
+			},
+			"http://bad.com": {
+				"content": 42
+			},
+			"http://good2.com": {
+				"content": "<html><body><p>Two</p></body></html>"
+			}
+		}
+	}`)
+
+	// The malformed entry rejects the whole input by default
+	if _, err := processInput(inputJsonBytes); err == nil {
+		t.Fatalf("Expected an error for a malformed URL entry, but got nil")
+	}
+
+	expectedOutput := OutputJson{
+		"/html/body/p": {
+			"http://good1.com": "One",
+			"http://good2.com": "Two",
+			// "http://bad.com" is skipped because its entry failed to decode
+		},
+	}
+
+	actualOutput, err := processInputWithOptions(context.Background(), inputJsonBytes, Options{LenientUrls: true})
+	if err != nil {
+		t.Fatalf("processInputWithOptions returned an unexpected error: %v", err)
+	}
+
+	if !reflect.DeepEqual(expectedOutput, actualOutput) {
+		expectedJson, _ := json.MarshalIndent(expectedOutput, "", "  ")
+		actualJson, _ := json.MarshalIndent(actualOutput, "", "  ")
+		t.Errorf("Unexpected output for lenient URLs.\nExpected:\n%s\nGot:\n%s", string(expectedJson), string(actualJson))
+	}
+}
+
+// Test case for rejecting content with tags outside the allowlist
+func TestProcessInput_AllowedTags(t *testing.T) {
+	inputJsonBytes := []byte(`{
+		"xpaths": ["/html/body/p"],
+		"allowed_tags": ["html", "body", "p", "b"],
+		"urls": {
+			"http://clean.com": {
+				"content": "<html><body><p>Clean <b>text</b></p></body></html>"
+			},
+			"http://injected.com": {
+				"content": "<html><body><p>Text</p><script>alert(1)</script></body></html>"
+			}
+		}
+	}`)
+
+	expectedOutput := OutputJson{
+		"/html/body/p": {
+			"http://clean.com": "Clean text",
+			// "http://injected.com" is skipped because <script> is not allowed
+		},
+	}
+
+	actualOutput, err := processInput(inputJsonBytes)
+	if err != nil {
+		t.Fatalf("processInput returned an unexpected error: %v", err)
+	}
+
+	if !reflect.DeepEqual(expectedOutput, actualOutput) {
+		expectedJson, _ := json.MarshalIndent(expectedOutput, "", "  ")
+		actualJson, _ := json.MarshalIndent(actualOutput, "", "  ")
+		t.Errorf("Unexpected output for allowed tags.\nExpected:\n%s\nGot:\n%s", string(expectedJson), string(actualJson))
+	}
+}
+
+// Test case for detecting a URL key that appears twice
+func TestProcessInput_DuplicateUrl(t *testing.T) {
+	inputFor := func(policy string) []byte {
+		return []byte(fmt.Sprintf(`{
+			"xpaths": ["/html/body/p"],
+			"on_duplicate_url": "%s",
+			"urls": {
+				"http://example.com": {"content": "<html><body><p>First</p></body></html>"},
+				"http://other.com": {"content": "<html><body><p>Other</p></body></html>"},
+				"http://example.com": {"content": "<html><body><p>Second</p></body></html>"}
+			}
+		}`, policy))
+	}
+
+	_, err := processInput(inputFor("error"))
+	if err == nil {
+		t.Fatalf("Expected an error for a duplicate URL key, but got nil")
+	}
+	if !strings.Contains(err.Error(), "http://example.com") {
+		t.Errorf("Expected the error to name the duplicate URL, got %v", err)
+	}
+
+	// Under "warn" the run proceeds with the last entry
+	actualOutput, err := processInput(inputFor("warn"))
+	if err != nil {
+		t.Fatalf("processInput returned an unexpected error: %v", err)
+	}
+	if got := actualOutput["/html/body/p"]["http://example.com"]; got != "Second" {
+		t.Errorf("Expected the last duplicate entry to be used, got %q", got)
+	}
+}
+
+// Test case for selector entries carrying a description
+func TestProcessInput_SelectorDescription(t *testing.T) {
+	inputJsonBytes := []byte(`{
+		"xpaths": [
+			"/html/body/p",
+			{"xpath": "[invalid-xpath", "description": "Checkout button label"}
+		],
+		"urls": {
+			"http://example.com": {
+				"content": "<html><body><p>Hello</p></body></html>"
+			}
+		}
+	}`)
+
+	var actualOutput OutputJson
+	var err error
+	stderr := captureStderr(t, func() {
+		actualOutput, err = processInput(inputJsonBytes)
+	})
+	if err != nil {
+		t.Fatalf("processInput returned an unexpected error: %v", err)
+	}
+
+	// Plain strings and objects are both accepted and keyed by their XPath
+	expectedOutput := OutputJson{
+		"/html/body/p": {
+			"http://example.com": "Hello",
+		},
+		"[invalid-xpath": {},
+	}
+	if !reflect.DeepEqual(expectedOutput, actualOutput) {
+		expectedJson, _ := json.MarshalIndent(expectedOutput, "", "  ")
+		actualJson, _ := json.MarshalIndent(actualOutput, "", "  ")
+		t.Errorf("Unexpected output for described selector.\nExpected:\n%s\nGot:\n%s", string(expectedJson), string(actualJson))
+	}
+
+	if !strings.Contains(stderr, "'[invalid-xpath' (Checkout button label)") {
+		t.Errorf("Expected the compile error to include the description, got:\n%s", stderr)
+	}
+}
+
+// Test case for bounding a slow XPath evaluation
+func TestProcessInput_EvalTimeout(t *testing.T) {
+	// Simulate a runaway selector, since real ones depend on machine speed
+	original := evaluateBytes
+	defer func() { evaluateBytes = original }()
+	slow := xmlpath.MustCompile("//title")
+	evaluateBytes = func(path *xmlpath.Path, root *xmlpath.Node) ([]byte, bool) {
+		if path == slow {
+			time.Sleep(2 * time.Second)
+		}
+		return original(path, root)
+	}
+	fast := xmlpath.MustCompile("/html/body/p")
+
+	root, err := decode(strings.NewReader("<html><head><title>Test Page</title></head><body><p>Hello</p></body></html>"), parseOptions{})
+	if err != nil {
+		t.Fatalf("decode returned an unexpected error: %v", err)
+	}
+
+	start := time.Now()
+	_, _, err = evaluateWithTimeout(slow, root, 50*time.Millisecond)
+	if elapsed := time.Since(start); elapsed > time.Second {
+		t.Errorf("Expected the slow evaluation to be abandoned near the timeout, took %v", elapsed)
+	}
+	if !errors.Is(err, errEvalTimeout) {
+		t.Errorf("Expected a timeout error, got %v", err)
+	}
+
+	// Evaluations within the limit are unaffected
+	resultBytes, ok, err := evaluateWithTimeout(fast, root, 50*time.Millisecond)
+	if err != nil || !ok || string(resultBytes) != "Hello" {
+		t.Errorf("Expected fast evaluation to return \"Hello\", got %q, %v, %v", resultBytes, ok, err)
+	}
+}
+
+// Test case for fallback selectors used when the primary XPath misses
+func TestProcessInput_Fallbacks(t *testing.T) {
+	inputJsonBytes := []byte(`{
+		"xpaths": [
+			{"xpath": "//span[@class='price']", "fallbacks": ["//div[@id='cost']", "//p"]}
+		],
+		"urls": {
+			"http://template-a.com": {
+				"content": "<html><body><span class=\"price\">10</span><p>Ignored</p></body></html>"
+			},
+			"http://template-b.com": {
+				"content": "<html><body><div id=\"cost\">20</div><p>Ignored</p></body></html>"
+			},
+			"http://template-c.com": {
+				"content": "<html><body><h1>No price</h1></body></html>"
+			}
+		}
+	}`)
+
+	expectedOutput := OutputJson{
+		"//span[@class='price']": {
+			// The primary XPath wins when it matches
+			"http://template-a.com": "10",
+			// Otherwise the first matching fallback is used, not later ones
+			"http://template-b.com": "20",
+			// "http://template-c.com" is omitted because nothing matched
+		},
+	}
+
+	var actualOutput OutputJson
+	var err error
+	stderr := captureStderr(t, func() {
+		actualOutput, err = processInput(inputJsonBytes)
+	})
+	if err != nil {
+		t.Fatalf("processInput returned an unexpected error: %v", err)
+	}
+
+	if !reflect.DeepEqual(expectedOutput, actualOutput) {
+		expectedJson, _ := json.MarshalIndent(expectedOutput, "", "  ")
+		actualJson, _ := json.MarshalIndent(actualOutput, "", "  ")
+		t.Errorf("Unexpected output for fallbacks.\nExpected:\n%s\nGot:\n%s", string(expectedJson), string(actualJson))
+	}
+
+	// Which fallback supplied a value is reported, for that URL only
+	if !strings.Contains(stderr, "'http://template-b.com'. Using the value of fallback '//div[@id='cost']'") {
+		t.Errorf("Expected a note naming the fallback used, got:\n%s", stderr)
+	}
+	if strings.Contains(stderr, "template-a.com") {
+		t.Errorf("Expected no fallback note where the primary XPath matched, got:\n%s", stderr)
+	}
+}
+
+// Test case for stopping a selector after the first mirror matches
+func TestProcessInput_FirstMatchPolicy(t *testing.T) {
+	// Count evaluations to confirm the remaining mirrors are not checked
+	original := evaluateBytes
+	defer func() { evaluateBytes = original }()
+	evaluations := 0
+	evaluateBytes = func(path *xmlpath.Path, root *xmlpath.Node) ([]byte, bool) {
+		evaluations++
+		return original(path, root)
+	}
+
+	inputJsonBytes := []byte(`{
+		"xpaths": [{"xpath": "//p[@id='status']", "url_policy": "first_match"}],
+		"urls": {
+			"http://mirror1.com": {"content": "<html><body><p id=\"status\">up</p></body></html>"},
+			"http://mirror2.com": {"content": "<html><body><p id=\"status\">up</p></body></html>"},
+			"http://mirror3.com": {"content": "<html><body><p id=\"status\">up</p></body></html>"}
+		}
+	}`)
+
+	expectedOutput := OutputJson{
+		"//p[@id='status']": {
+			"http://mirror1.com": "up",
+		},
+	}
+
+	actualOutput, err := processInput(inputJsonBytes)
+	if err != nil {
+		t.Fatalf("processInput returned an unexpected error: %v", err)
+	}
+
+	if !reflect.DeepEqual(expectedOutput, actualOutput) {
+		expectedJson, _ := json.MarshalIndent(expectedOutput, "", "  ")
+		actualJson, _ := json.MarshalIndent(actualOutput, "", "  ")
+		t.Errorf("Unexpected output for first_match policy.\nExpected:\n%s\nGot:\n%s", string(expectedJson), string(actualJson))
+	}
+	if evaluations != 1 {
+		t.Errorf("Expected evaluation to stop after the first match, got %d evaluations", evaluations)
+	}
+}
+
+// Test case for combining several attributes into one value
+func TestProcessInput_Attributes(t *testing.T) {
+	inputJsonBytes := []byte(`{
+		"xpaths": [
+			{"xpath": "//div[@class='product']", "attributes": ["data-sku", "data-variant"], "attribute_separator": "-"}
+		],
+		"urls": {
+			"http://shop.com/a": {
+				"content": "<html><body><div class=\"product\" data-variant=\"1\" data-sku=\"abc\">A</div></body></html>"
+			},
+			"http://shop.com/b": {
+				"content": "<html><body><div class=\"product\" data-sku=\"def\">B</div></body></html>"
+			}
+		}
+	}`)
+
+	var actualOutput OutputJson
+	var err error
+	stderr := captureStderr(t, func() {
+		actualOutput, err = processInput(inputJsonBytes)
+	})
+	if err != nil {
+		t.Fatalf("processInput returned an unexpected error: %v", err)
+	}
+
+	// Attributes are joined in the listed order; a missing one gives no value
+	expectedOutput := OutputJson{
+		"//div[@class='product']": {
+			"http://shop.com/a": "abc-1",
+		},
+	}
+	if !reflect.DeepEqual(expectedOutput, actualOutput) {
+		expectedJson, _ := json.MarshalIndent(expectedOutput, "", "  ")
+		actualJson, _ := json.MarshalIndent(actualOutput, "", "  ")
+		t.Errorf("Unexpected output for combined attributes.\nExpected:\n%s\nGot:\n%s", string(expectedJson), string(actualJson))
+	}
+
+	if !strings.Contains(stderr, "http://shop.com/b") || !strings.Contains(stderr, "no attribute 'data-variant'") {
+		t.Errorf("Expected a warning naming the missing attribute, got:\n%s", stderr)
+	}
+}
+
+// Test case for a selector that only applies when its precondition matches
+func TestProcessInput_WhenPrecondition(t *testing.T) {
+	inputJsonBytes := []byte(`{
+		"xpaths": [
+			{"xpath": "//button[@id='add-to-cart']", "when": "//span[@class='in-stock']"}
+		],
+		"urls": {
+			"http://shop.com/in-stock": {
+				"content": "<html><body><span class=\"in-stock\">Available</span><button id=\"add-to-cart\">Add</button></body></html>"
+			},
+			"http://shop.com/sold-out": {
+				"content": "<html><body><span class=\"sold-out\">Gone</span><button id=\"add-to-cart\">Notify me</button></body></html>"
+			}
+		}
+	}`)
+
+	// The button exists on both pages, but only the in-stock page is checked
+	expectedOutput := OutputJson{
+		"//button[@id='add-to-cart']": {
+			"http://shop.com/in-stock": "Add",
+		},
+	}
+
+	actualOutput, err := processInput(inputJsonBytes)
+	if err != nil {
+		t.Fatalf("processInput returned an unexpected error: %v", err)
+	}
+
+	if !reflect.DeepEqual(expectedOutput, actualOutput) {
+		expectedJson, _ := json.MarshalIndent(expectedOutput, "", "  ")
+		actualJson, _ := json.MarshalIndent(actualOutput, "", "  ")
+		t.Errorf("Unexpected output for when precondition.\nExpected:\n%s\nGot:\n%s", string(expectedJson), string(actualJson))
+	}
+
+	// The sold-out pair is skipped, so it is neither a record nor a failing test
+	input, err := unmarshalInput(inputJsonBytes, Options{})
+	if err != nil {
+		t.Fatalf("unmarshalInput returned an unexpected error: %v", err)
+	}
+	run, err := runInput(context.Background(), input, Options{})
+	if err != nil {
+		t.Fatalf("runInput returned an unexpected error: %v", err)
+	}
+	expectedRecords := []record{
+		{Selector: "//button[@id='add-to-cart']", Url: "http://shop.com/in-stock", Value: "Add", Matched: true},
+	}
+	if actualRecords := toRecords(run); !reflect.DeepEqual(expectedRecords, actualRecords) {
+		t.Errorf("Unexpected records.\nExpected: %+v\nGot: %+v", expectedRecords, actualRecords)
+	}
+	if tap := toTap(run); !strings.Contains(tap, "ok 2 - //button[@id='add-to-cart'] http://shop.com/sold-out # SKIP") {
+		t.Errorf("Expected the sold-out pair to be skipped in TAP, got:\n%s", tap)
+	}
+}
+
+// Test case for concatenating the values of several XPaths
+func TestProcessInput_Concat(t *testing.T) {
+	inputJsonBytes := []byte(`{
+		"xpaths": [
+			{"xpath": "//span[@class='first']", "concat": ["//span[@class='last']"]}
+		],
+		"urls": {
+			"http://people.com/jane": {
+				"content": "<html><body><span class=\"first\">Jane</span> <span class=\"last\">Doe</span></body></html>"
+			},
+			"http://people.com/mononym": {
+				"content": "<html><body><span class=\"first\">Cher</span></body></html>"
+			}
+		}
+	}`)
+
+	// A missing part gives no value rather than a partial one
+	expectedOutput := OutputJson{
+		"//span[@class='first']": {
+			"http://people.com/jane": "Jane Doe",
+		},
+	}
+
+	actualOutput, err := processInput(inputJsonBytes)
+	if err != nil {
+		t.Fatalf("processInput returned an unexpected error: %v", err)
+	}
+
+	if !reflect.DeepEqual(expectedOutput, actualOutput) {
+		expectedJson, _ := json.MarshalIndent(expectedOutput, "", "  ")
+		actualJson, _ := json.MarshalIndent(actualOutput, "", "  ")
+		t.Errorf("Unexpected output for concatenated selectors.\nExpected:\n%s\nGot:\n%s", string(expectedJson), string(actualJson))
+	}
+}
+
+// Test case for validating extracted values against a named type
+func TestProcessInput_ExpectType(t *testing.T) {
+	inputJsonBytes := []byte(`{
+		"xpaths": [
+			{"xpath": "//span[@class='stock']", "expect_type": "integer"}
+		],
+		"urls": {
+			"http://shop.com/a": {"content": "<html><body><span class=\"stock\">42</span></body></html>"},
+			"http://shop.com/b": {"content": "<html><body><span class=\"stock\">abc</span></body></html>"}
+		}
+	}`)
+
+	var actualOutput OutputJson
+	var err error
+	stderr := captureStderr(t, func() {
+		actualOutput, err = processInput(inputJsonBytes)
+	})
+	if err != nil {
+		t.Fatalf("processInput returned an unexpected error: %v", err)
+	}
+
+	// The non-integer value is reported and omitted
+	expectedOutput := OutputJson{
+		"//span[@class='stock']": {
+			"http://shop.com/a": "42",
+		},
+	}
+	if !reflect.DeepEqual(expectedOutput, actualOutput) {
+		expectedJson, _ := json.MarshalIndent(expectedOutput, "", "  ")
+		actualJson, _ := json.MarshalIndent(actualOutput, "", "  ")
+		t.Errorf("Unexpected output for expect_type.\nExpected:\n%s\nGot:\n%s", string(expectedJson), string(actualJson))
+	}
+
+	if !strings.Contains(stderr, "http://shop.com/b") || !strings.Contains(stderr, `"abc" is not a valid integer`) {
+		t.Errorf("Expected a warning naming the invalid value, got:\n%s", stderr)
+	}
+}
+
+func TestProcessInput_ExpectDistinctCount(t *testing.T) {
+	inputJsonBytes := []byte(`{
+		"xpaths": [
+			{"xpath": "//li[@class='tab']", "expect_distinct_count": 3}
+		],
+		"urls": {
+			"http://example.com/a": {"content": "<html><body><ul><li class=\"tab\">Home</li><li class=\"tab\">Specs</li><li class=\"tab\">Home</li><li class=\"tab\">Reviews</li><li class=\"tab\">Specs</li></ul></body></html>"},
+			"http://example.com/b": {"content": "<html><body><ul><li class=\"tab\">Home</li><li class=\"tab\">Home</li><li class=\"tab\">Specs</li><li class=\"tab\">Specs</li><li class=\"tab\">Specs</li></ul></body></html>"}
+		}
+	}`)
+
+	var actualOutput OutputJson
+	var err error
+	stderr := captureStderr(t, func() {
+		actualOutput, err = processInput(inputJsonBytes)
+	})
+	if err != nil {
+		t.Fatalf("processInput returned an unexpected error: %v", err)
+	}
+
+	// Both pages match 5 tabs; only the first collapses to 3 distinct labels
+	expectedOutput := OutputJson{
+		"//li[@class='tab']": {
+			"http://example.com/a": "Home",
+		},
+	}
+	if !reflect.DeepEqual(expectedOutput, actualOutput) {
+		expectedJson, _ := json.MarshalIndent(expectedOutput, "", "  ")
+		actualJson, _ := json.MarshalIndent(actualOutput, "", "  ")
+		t.Errorf("Unexpected output for expect_distinct_count.\nExpected:\n%s\nGot:\n%s", string(expectedJson), string(actualJson))
+	}
+
+	if !strings.Contains(stderr, "http://example.com/b") || !strings.Contains(stderr, "matched 2 distinct values, expected 3") {
+		t.Errorf("Expected a warning naming the distinct count, got:\n%s", stderr)
+	}
+}
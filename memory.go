@@ -0,0 +1,69 @@
+package main
+
+import (
+	"fmt"
+	"io"
+	"runtime"
+	"time"
+)
+
+// memorySampleInterval is how often a memory profile samples heap usage.
+const memorySampleInterval = 10 * time.Millisecond
+
+// memoryProfile tracks heap usage while processing runs. Go has no allocator
+// hook, so the peak is the largest live heap seen by a sampler running every
+// memorySampleInterval, plus one sample when profiling stops.
+type memoryProfile struct {
+	startTotal uint64 // Cumulative bytes allocated when profiling began
+	peakHeap   uint64
+	allocated  uint64 // Bytes allocated while profiling
+	done       chan struct{}
+	stopped    chan struct{}
+}
+
+func startMemoryProfile() *memoryProfile {
+	var stats runtime.MemStats
+	runtime.ReadMemStats(&stats)
+	p := &memoryProfile{
+		startTotal: stats.TotalAlloc,
+		peakHeap:   stats.HeapAlloc,
+		done:       make(chan struct{}),
+		stopped:    make(chan struct{}),
+	}
+	go func() {
+		defer close(p.stopped)
+		ticker := time.NewTicker(memorySampleInterval)
+		defer ticker.Stop()
+		for {
+			select {
+			case <-ticker.C:
+				p.sample()
+			case <-p.done:
+				return
+			}
+		}
+	}()
+	return p
+}
+
+// sample records the current heap usage.
+func (p *memoryProfile) sample() {
+	var stats runtime.MemStats
+	runtime.ReadMemStats(&stats)
+	if stats.HeapAlloc > p.peakHeap {
+		p.peakHeap = stats.HeapAlloc
+	}
+	p.allocated = stats.TotalAlloc - p.startTotal
+}
+
+// stop ends sampling and takes a final sample.
+func (p *memoryProfile) stop() *memoryProfile {
+	close(p.done)
+	<-p.stopped
+	p.sample()
+	return p
+}
+
+func (p *memoryProfile) write(w io.Writer) {
+	fmt.Fprintf(w, "memory peak heap %d bytes, allocated %d bytes\n", p.peakHeap, p.allocated)
+}
@@ -0,0 +1,32 @@
+package main
+
+import (
+	"bytes"
+	"context"
+	"fmt"
+	"testing"
+)
+
+// Test case for reporting peak heap usage of a run
+func TestProcessInput_MemoryReport(t *testing.T) {
+	inputJsonBytes := []byte(`{
+		"xpaths": ["/html/body/p"],
+		"urls": {
+			"http://example.com": {"content": "<html><body><p>Hello</p></body></html>"}
+		}
+	}`)
+
+	var report bytes.Buffer
+	_, err := processInputWithOptions(context.Background(), inputJsonBytes, Options{MemoryReport: &report})
+	if err != nil {
+		t.Fatalf("processInputWithOptions returned an unexpected error: %v", err)
+	}
+
+	var peakHeap, allocated uint64
+	if _, err := fmt.Sscanf(report.String(), "memory peak heap %d bytes, allocated %d bytes\n", &peakHeap, &allocated); err != nil {
+		t.Fatalf("Failed to parse memory report %q: %v", report.String(), err)
+	}
+	if peakHeap == 0 || allocated == 0 {
+		t.Errorf("Expected a nonzero peak heap and allocation, got %q", report.String())
+	}
+}
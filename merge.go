@@ -0,0 +1,111 @@
+package main
+
+import (
+	"encoding/json"
+	"fmt"
+	"io"
+	"os"
+	"strings"
+)
+
+// Conflict policies for selectors and URLs defined in more than one --input file.
+const (
+	onConflictOverride = "override" // The later file's entry wins
+	onConflictError    = "error"    // Reject the merged input
+)
+
+// stringList is a repeatable string flag.
+type stringList []string
+
+func (l *stringList) String() string { return strings.Join(*l, ",") }
+
+func (l *stringList) Set(value string) error {
+	*l = append(*l, value)
+	return nil
+}
+
+// readInputs returns the input bytes for a run: stdin when no input files are given,
+// otherwise the files merged in order with mergeInputs.
+func readInputs(stdin io.Reader, paths []string, onConflict string, opts Options) ([]byte, error) {
+	if len(paths) == 0 {
+		return io.ReadAll(stdin)
+	}
+
+	inputs := make([]InputJson, 0, len(paths))
+	for _, path := range paths {
+		inputBytes, err := os.ReadFile(path)
+		if err != nil {
+			return nil, err
+		}
+		input, err := unmarshalInput(inputBytes, opts)
+		if err != nil {
+			return nil, fmt.Errorf("%s: %w", path, err)
+		}
+		inputs = append(inputs, input)
+	}
+
+	merged, err := mergeInputs(inputs, onConflict)
+	if err != nil {
+		return nil, err
+	}
+	return json.Marshal(merged)
+}
+
+// mergeInputs combines inputs in order. Selectors and URLs are unioned, with a
+// selector (by XPath) or URL present in more than one input resolved by onConflict;
+// an overriding selector keeps the position of the one it replaces. Repeats within
+// one input are left to dedupXpaths. A setting given a non-default value in a
+// later input overrides earlier ones. Because an explicit false or 0 cannot be told
+// apart from an omitted setting, normalize_tag_case and canonicalize_xpaths are on
+// if any input turns them on, and a later input cannot clear eval_timeout_ms.
+func mergeInputs(inputs []InputJson, onConflict string) (InputJson, error) {
+	if onConflict != onConflictOverride && onConflict != onConflictError {
+		return InputJson{}, fmt.Errorf("invalid on-conflict policy %q: expected %q or %q", onConflict, onConflictOverride, onConflictError)
+	}
+
+	merged := InputJson{Urls: make(map[string]UrlData)}
+	selectorIndex := make(map[string]int) // Position of each XPath in merged.Xpaths
+	for _, input := range inputs {
+		inThisInput := make(map[string]bool)
+		for _, sel := range input.Xpaths {
+			if i, exists := selectorIndex[sel.Xpath]; exists && !inThisInput[sel.Xpath] {
+				if onConflict == onConflictError {
+					return InputJson{}, fmt.Errorf("selector '%s' is defined in more than one input", sel.Xpath)
+				}
+				merged.Xpaths[i] = sel
+			} else {
+				if !exists {
+					selectorIndex[sel.Xpath] = len(merged.Xpaths)
+				}
+				merged.Xpaths = append(merged.Xpaths, sel)
+			}
+			inThisInput[sel.Xpath] = true
+		}
+		for url, urlData := range input.Urls {
+			if _, exists := merged.Urls[url]; exists && onConflict == onConflictError {
+				return InputJson{}, fmt.Errorf("URL '%s' is defined in more than one input", url)
+			}
+			merged.Urls[url] = urlData
+		}
+
+		if input.NormalizeTagCase {
+			merged.NormalizeTagCase = true
+		}
+		if input.AllowedTags != nil {
+			merged.AllowedTags = input.AllowedTags
+		}
+		if input.OnDuplicateUrl != "" {
+			merged.OnDuplicateUrl = input.OnDuplicateUrl
+		}
+		if input.EvalTimeoutMs != 0 {
+			merged.EvalTimeoutMs = input.EvalTimeoutMs
+		}
+		if input.CanonicalizeXpaths {
+			merged.CanonicalizeXpaths = true
+		}
+		if input.InvalidEncoding != "" {
+			merged.InvalidEncoding = input.InvalidEncoding
+		}
+	}
+	return merged, nil
+}
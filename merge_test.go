@@ -0,0 +1,119 @@
+package main
+
+import (
+	"encoding/json"
+	"os"
+	"path/filepath"
+	"reflect"
+	"strings"
+	"testing"
+)
+
+// Test case for merging several input files into one run
+func TestReadInputs_Merge(t *testing.T) {
+	dir := t.TempDir()
+	first := filepath.Join(dir, "first.json")
+	second := filepath.Join(dir, "second.json")
+	writeFile := func(path, contents string) {
+		if err := os.WriteFile(path, []byte(contents), 0o644); err != nil {
+			t.Fatalf("Failed to write %s: %v", path, err)
+		}
+	}
+	writeFile(first, `{
+		"xpaths": ["/html/body/p"],
+		"urls": {
+			"http://example.com": {"content": "<html><head><title>Old</title></head><body><p>Hello</p></body></html>"}
+		}
+	}`)
+	writeFile(second, `{
+		"xpaths": ["//title"],
+		"urls": {
+			"http://example.com": {"content": "<html><head><title>New</title></head><body><p>Hello</p></body></html>"},
+			"http://other.com": {"content": "<html><head><title>Other</title></head><body><p>Hi</p></body></html>"}
+		}
+	}`)
+
+	inputBytes, err := readInputs(strings.NewReader(""), []string{first, second}, onConflictOverride, Options{})
+	if err != nil {
+		t.Fatalf("readInputs returned an unexpected error: %v", err)
+	}
+
+	// Both files' selectors run, and the later file's entry for a shared URL wins
+	expectedOutput := OutputJson{
+		"/html/body/p": {
+			"http://example.com": "Hello",
+			"http://other.com":   "Hi",
+		},
+		"//title": {
+			"http://example.com": "New",
+			"http://other.com":   "Other",
+		},
+	}
+
+	actualOutput, err := processInput(inputBytes)
+	if err != nil {
+		t.Fatalf("processInput returned an unexpected error: %v", err)
+	}
+
+	if !reflect.DeepEqual(expectedOutput, actualOutput) {
+		expectedJson, _ := json.MarshalIndent(expectedOutput, "", "  ")
+		actualJson, _ := json.MarshalIndent(actualOutput, "", "  ")
+		t.Errorf("Unexpected output for merged inputs.\nExpected:\n%s\nGot:\n%s", string(expectedJson), string(actualJson))
+	}
+
+	// The error policy rejects the shared URL instead
+	if _, err := readInputs(strings.NewReader(""), []string{first, second}, onConflictError, Options{}); err == nil {
+		t.Errorf("Expected an error for a URL defined in both inputs, but got nil")
+	}
+}
+
+// Test case for a selector defined with different settings in two input files
+func TestReadInputs_MergeSelectorConflict(t *testing.T) {
+	dir := t.TempDir()
+	first := filepath.Join(dir, "first.json")
+	second := filepath.Join(dir, "second.json")
+	writeFile := func(path, contents string) {
+		if err := os.WriteFile(path, []byte(contents), 0o644); err != nil {
+			t.Fatalf("Failed to write %s: %v", path, err)
+		}
+	}
+	writeFile(first, `{
+		"xpaths": [{"xpath": "//title", "transform": ["upper"]}, "/html/body/p"],
+		"urls": {
+			"http://example.com": {"content": "<html><head><title>Test Page</title></head><body><p>Hello</p></body></html>"}
+		}
+	}`)
+	writeFile(second, `{
+		"xpaths": [{"xpath": "//title", "transform": ["lower"]}],
+		"urls": {}
+	}`)
+
+	inputBytes, err := readInputs(strings.NewReader(""), []string{first, second}, onConflictOverride, Options{})
+	if err != nil {
+		t.Fatalf("readInputs returned an unexpected error: %v", err)
+	}
+
+	// The later file's settings for //title win, in the earlier file's position
+	var merged InputJson
+	if err := json.Unmarshal(inputBytes, &merged); err != nil {
+		t.Fatalf("Failed to parse merged input: %v", err)
+	}
+	expectedSelectors := []Selector{{Xpath: "//title", Transform: []string{"lower"}}, {Xpath: "/html/body/p"}}
+	if !reflect.DeepEqual(expectedSelectors, merged.Xpaths) {
+		t.Errorf("Unexpected merged selectors.\nExpected: %+v\nGot: %+v", expectedSelectors, merged.Xpaths)
+	}
+
+	actualOutput, err := processInput(inputBytes)
+	if err != nil {
+		t.Fatalf("processInput returned an unexpected error: %v", err)
+	}
+	if value := actualOutput["//title"]["http://example.com"]; value != "test page" {
+		t.Errorf("Expected the overriding transform to apply, got %q", value)
+	}
+
+	// The error policy rejects the selector defined in both inputs
+	_, err = readInputs(strings.NewReader(""), []string{first, second}, onConflictError, Options{})
+	if err == nil || !strings.Contains(err.Error(), "selector '//title'") {
+		t.Errorf("Expected an error naming the conflicting selector, got %v", err)
+	}
+}
@@ -0,0 +1,24 @@
+package main
+
+import (
+	"crypto/sha256"
+	"encoding/hex"
+)
+
+// provenance wraps a run's results so that stored output describes how it was made.
+type provenance struct {
+	InputSha256 string      `json:"input_sha256"`
+	Config      runConfig   `json:"config"`
+	Results     interface{} `json:"results"`
+}
+
+// withProvenance wraps results with the SHA-256 of the input bytes and the
+// configuration the run resolved.
+func withProvenance(inputBytes []byte, config runConfig, results interface{}) provenance {
+	sum := sha256.Sum256(inputBytes)
+	return provenance{
+		InputSha256: hex.EncodeToString(sum[:]),
+		Config:      config,
+		Results:     results,
+	}
+}
@@ -0,0 +1,47 @@
+package main
+
+import (
+	"encoding/json"
+	"reflect"
+	"testing"
+)
+
+// Test case for the provenance wrapper around results
+func TestWithProvenance(t *testing.T) {
+	inputJsonBytes := []byte(`{"xpaths": ["//title"], "urls": {"http://example.com": {"content": "<html><head><title>Test Page</title></head></html>"}}}`)
+
+	output, err := processInput(inputJsonBytes)
+	if err != nil {
+		t.Fatalf("processInput returned an unexpected error: %v", err)
+	}
+	config, err := resolveConfig(inputJsonBytes, Options{WithProvenance: true})
+	if err != nil {
+		t.Fatalf("resolveConfig returned an unexpected error: %v", err)
+	}
+
+	wrappedJson, err := marshalOutput(withProvenance(inputJsonBytes, config, output), false)
+	if err != nil {
+		t.Fatalf("marshalOutput returned an unexpected error: %v", err)
+	}
+
+	var actual struct {
+		InputSha256 string     `json:"input_sha256"`
+		Config      runConfig  `json:"config"`
+		Results     OutputJson `json:"results"`
+	}
+	if err := json.Unmarshal(wrappedJson, &actual); err != nil {
+		t.Fatalf("Failed to parse provenance output: %v", err)
+	}
+
+	// Precomputed with sha256sum over the exact input bytes above
+	const expectedSha256 = "b297e6f0a93bde839723d303413d90be12f38a8a3d0ca13d11753fe79d25792c"
+	if actual.InputSha256 != expectedSha256 {
+		t.Errorf("Unexpected input hash: got %s, want %s", actual.InputSha256, expectedSha256)
+	}
+	if !actual.Config.WithProvenance || actual.Config.Urls != 1 {
+		t.Errorf("Unexpected config in provenance: %+v", actual.Config)
+	}
+	if !reflect.DeepEqual(output, actual.Results) {
+		t.Errorf("Expected results to be embedded unchanged, got %v", actual.Results)
+	}
+}
@@ -0,0 +1,52 @@
+package main
+
+import (
+	"bytes"
+	"context"
+	"encoding/json"
+	"fmt"
+	"io"
+	"net/http"
+	"time"
+)
+
+// rendererTimeout bounds a single render request, since headless rendering can hang.
+const rendererTimeout = 60 * time.Second
+
+// renderRequest is the JSON body posted to the renderer endpoint.
+type renderRequest struct {
+	URL string `json:"url"`
+}
+
+// renderURL asks the renderer at rendererURL to load pageURL in a headless browser
+// and returns the rendered HTML (the response body). The request is abandoned if
+// ctx is done first, so a hung renderer cannot outlast the run.
+func renderURL(ctx context.Context, rendererURL, pageURL string) (string, error) {
+	body, err := json.Marshal(renderRequest{URL: pageURL})
+	if err != nil {
+		return "", fmt.Errorf("error marshalling render request: %w", err)
+	}
+
+	req, err := http.NewRequestWithContext(ctx, http.MethodPost, rendererURL, bytes.NewReader(body))
+	if err != nil {
+		return "", fmt.Errorf("error creating render request: %w", err)
+	}
+	req.Header.Set("Content-Type", "application/json")
+
+	client := &http.Client{Timeout: rendererTimeout}
+	resp, err := client.Do(req)
+	if err != nil {
+		return "", fmt.Errorf("error contacting renderer: %w", err)
+	}
+	defer resp.Body.Close()
+
+	if resp.StatusCode != http.StatusOK {
+		return "", fmt.Errorf("renderer returned status %s", resp.Status)
+	}
+
+	html, err := io.ReadAll(resp.Body)
+	if err != nil {
+		return "", fmt.Errorf("error reading rendered HTML: %w", err)
+	}
+	return string(html), nil
+}
@@ -0,0 +1,87 @@
+package main
+
+import (
+	"context"
+	"encoding/json"
+	"errors"
+	"io"
+	"net/http"
+	"net/http/httptest"
+	"reflect"
+	"testing"
+	"time"
+)
+
+// Test case for rendering a client-side page through a stub renderer service
+func TestProcessInput_Render(t *testing.T) {
+	var requested []string
+	renderer := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		body, _ := io.ReadAll(r.Body)
+		var req renderRequest
+		if err := json.Unmarshal(body, &req); err != nil {
+			http.Error(w, err.Error(), http.StatusBadRequest)
+			return
+		}
+		requested = append(requested, req.URL)
+		w.Write([]byte("<html><body><p>Rendered</p></body></html>"))
+	}))
+	defer renderer.Close()
+
+	inputJsonBytes := []byte(`{
+		"xpaths": ["/html/body/p"],
+		"urls": {
+			"http://spa.com": {
+				"content": "<html><body><div id=\"app\"></div></body></html>",
+				"render": true
+			},
+			"http://static.com": {
+				"content": "<html><body><p>Static</p></body></html>"
+			}
+		}
+	}`)
+
+	expectedOutput := OutputJson{
+		"/html/body/p": {
+			// The rendered HTML replaces the empty client-side shell
+			"http://spa.com": "Rendered",
+			// Static content remains the default path
+			"http://static.com": "Static",
+		},
+	}
+
+	actualOutput, err := processInputWithOptions(context.Background(), inputJsonBytes, Options{RendererURL: renderer.URL})
+	if err != nil {
+		t.Fatalf("processInputWithOptions returned an unexpected error: %v", err)
+	}
+
+	if !reflect.DeepEqual(expectedOutput, actualOutput) {
+		expectedJson, _ := json.MarshalIndent(expectedOutput, "", "  ")
+		actualJson, _ := json.MarshalIndent(actualOutput, "", "  ")
+		t.Errorf("Unexpected output for rendered URL.\nExpected:\n%s\nGot:\n%s", string(expectedJson), string(actualJson))
+	}
+
+	// Only the URL marked for rendering should be submitted to the renderer
+	if !reflect.DeepEqual(requested, []string{"http://spa.com"}) {
+		t.Errorf("Expected only http://spa.com to be rendered, got %v", requested)
+	}
+}
+
+// Test case for abandoning a hung render when the run is cancelled
+func TestRenderURL_Cancelled(t *testing.T) {
+	renderer := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		<-r.Context().Done() // Never responds on its own
+	}))
+	defer renderer.Close()
+
+	ctx, cancel := context.WithTimeout(context.Background(), 50*time.Millisecond)
+	defer cancel()
+
+	start := time.Now()
+	_, err := renderURL(ctx, renderer.URL, "http://spa.com")
+	if !errors.Is(err, context.DeadlineExceeded) {
+		t.Fatalf("Expected the render to stop with the context, got %v", err)
+	}
+	if elapsed := time.Since(start); elapsed > 5*time.Second {
+		t.Errorf("Expected the render to stop promptly, took %v", elapsed)
+	}
+}
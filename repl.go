@@ -0,0 +1,52 @@
+package main
+
+import (
+	"bufio"
+	"context"
+	"encoding/json"
+	"fmt"
+	"io"
+	"os"
+	"strings"
+)
+
+// runRepl parses every URL in the input once, then reads XPaths from in, one per
+// line, and writes for each line a JSON object mapping URL to matched value.
+// Lines whose XPath fails to compile produce an error object instead.
+func runRepl(inputBytes []byte, opts Options, in io.Reader, out io.Writer) error {
+	input, err := unmarshalInput(inputBytes, opts)
+	if err != nil {
+		return err
+	}
+
+	pages := parsePages(context.Background(), input, opts)
+	fmt.Fprintf(os.Stderr, "Loaded %d URLs. Enter one XPath per line.\n", len(pages))
+
+	scanner := bufio.NewScanner(in)
+	encoder := json.NewEncoder(out)
+	for scanner.Scan() {
+		xpathStr := strings.TrimSpace(scanner.Text())
+		if xpathStr == "" {
+			continue
+		}
+
+		compiled, err := compileSelector(Selector{Xpath: xpathStr}, input.NormalizeTagCase)
+		if err != nil {
+			if err := encoder.Encode(map[string]string{"error": err.Error()}); err != nil {
+				return err
+			}
+			continue
+		}
+
+		results := make(map[string]string)
+		for _, page := range pages {
+			if value, ok, _ := compiled.evaluate(page.root, 0); ok {
+				results[page.url] = value
+			}
+		}
+		if err := encoder.Encode(results); err != nil {
+			return err
+		}
+	}
+	return scanner.Err()
+}
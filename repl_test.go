@@ -0,0 +1,49 @@
+package main
+
+import (
+	"bytes"
+	"encoding/json"
+	"reflect"
+	"strings"
+	"testing"
+)
+
+// Test case for evaluating selectors typed into the REPL
+func TestRunRepl(t *testing.T) {
+	inputJsonBytes := []byte(`{
+		"xpaths": [],
+		"urls": {
+			"http://example.com": {
+				"content": "<html><head><title>Test Page</title></head><body><p>Hello</p></body></html>"
+			},
+			"http://other.com": {
+				"content": "<html><body><p>Other</p></body></html>"
+			}
+		}
+	}`)
+
+	var out bytes.Buffer
+	in := strings.NewReader("/html/body/p\n\n//title\n")
+	if err := runRepl(inputJsonBytes, Options{}, in, &out); err != nil {
+		t.Fatalf("runRepl returned an unexpected error: %v", err)
+	}
+
+	// One result line per selector line; blank lines are ignored
+	lines := strings.Split(strings.TrimRight(out.String(), "\n"), "\n")
+	expectedLines := []map[string]string{
+		{"http://example.com": "Hello", "http://other.com": "Other"},
+		{"http://example.com": "Test Page"},
+	}
+	if len(lines) != len(expectedLines) {
+		t.Fatalf("Expected %d result lines, got %d:\n%s", len(expectedLines), len(lines), out.String())
+	}
+	for i, line := range lines {
+		var actual map[string]string
+		if err := json.Unmarshal([]byte(line), &actual); err != nil {
+			t.Fatalf("Failed to parse result line %q: %v", line, err)
+		}
+		if !reflect.DeepEqual(expectedLines[i], actual) {
+			t.Errorf("Unexpected result for line %d.\nExpected: %v\nGot: %v", i+1, expectedLines[i], actual)
+		}
+	}
+}
@@ -0,0 +1,29 @@
+package main
+
+import (
+	"net/url"
+	"regexp"
+	"strings"
+)
+
+// urlReference matches values that look like URL references: an absolute URL
+// with a scheme, or a reference starting with //, /, ./, ../, ? or #.
+var urlReference = regexp.MustCompile(`^([A-Za-z][A-Za-z0-9+.-]*:|//|/|\./|\.\./|\?|#)`)
+
+// resolveUrl resolves value, a possibly relative URL such as an extracted href,
+// against base. Values that do not look like URL references, such as plain words
+// or numbers, are returned unchanged, as is value if either does not parse.
+func resolveUrl(base, value string) string {
+	if !urlReference.MatchString(value) || strings.ContainsAny(value, " \t\r\n") {
+		return value
+	}
+	baseUrl, err := url.Parse(base)
+	if err != nil {
+		return value
+	}
+	ref, err := url.Parse(value)
+	if err != nil {
+		return value
+	}
+	return baseUrl.ResolveReference(ref).String()
+}
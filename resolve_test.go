@@ -0,0 +1,55 @@
+package main
+
+import (
+	"encoding/json"
+	"reflect"
+	"testing"
+)
+
+// Test case for resolving relative hrefs to absolute URLs
+func TestProcessInput_ResolveUrls(t *testing.T) {
+	inputJsonBytes := []byte(`{
+		"xpaths": [
+			{"xpath": "//a[@id='product']/@href", "resolve_urls": true},
+			{"xpath": "//a[@id='product']", "resolve_urls": true},
+			{"xpath": "//span[@class='badge']", "resolve_urls": true}
+		],
+		"urls": {
+			"http://shop.com/catalog/page": {
+				"content": "<html><body><a id=\"product\" href=\"/products/1\">Product one</a><span class=\"badge\">Sale</span></body></html>"
+			},
+			"http://mirror.com/catalog/page": {
+				"base_url": "https://shop.com/catalog/",
+				"content": "<html><body><a id=\"product\" href=\"../products/2\">Product two</a><span class=\"badge\">42</span></body></html>"
+			}
+		}
+	}`)
+
+	expectedOutput := OutputJson{
+		"//a[@id='product']/@href": {
+			"http://shop.com/catalog/page":   "http://shop.com/products/1",
+			"http://mirror.com/catalog/page": "https://shop.com/products/2",
+		},
+		// Text that is not a URL is left as it is
+		"//a[@id='product']": {
+			"http://shop.com/catalog/page":   "Product one",
+			"http://mirror.com/catalog/page": "Product two",
+		},
+		// Single words and numbers are not mistaken for relative URLs
+		"//span[@class='badge']": {
+			"http://shop.com/catalog/page":   "Sale",
+			"http://mirror.com/catalog/page": "42",
+		},
+	}
+
+	actualOutput, err := processInput(inputJsonBytes)
+	if err != nil {
+		t.Fatalf("processInput returned an unexpected error: %v", err)
+	}
+
+	if !reflect.DeepEqual(expectedOutput, actualOutput) {
+		expectedJson, _ := json.MarshalIndent(expectedOutput, "", "  ")
+		actualJson, _ := json.MarshalIndent(actualOutput, "", "  ")
+		t.Errorf("Unexpected output for resolved URLs.\nExpected:\n%s\nGot:\n%s", string(expectedJson), string(actualJson))
+	}
+}
@@ -0,0 +1,38 @@
+package main
+
+import (
+	"crypto/rand"
+	"fmt"
+	"time"
+)
+
+// runMeta wraps a run's results with an identifier and timestamps, for
+// correlating stored output with logs.
+type runMeta struct {
+	RunId      string      `json:"run_id"`
+	StartedAt  string      `json:"started_at"`
+	FinishedAt string      `json:"finished_at"`
+	Results    interface{} `json:"results"`
+}
+
+// newRunId returns a random (version 4) UUID.
+func newRunId() (string, error) {
+	var b [16]byte
+	if _, err := rand.Read(b[:]); err != nil {
+		return "", err
+	}
+	b[6] = b[6]&0x0f | 0x40 // Version 4
+	b[8] = b[8]&0x3f | 0x80 // RFC 4122 variant
+	return fmt.Sprintf("%x-%x-%x-%x-%x", b[0:4], b[4:6], b[6:8], b[8:10], b[10:16]), nil
+}
+
+// withRunMeta wraps results with runId and the run's start and finish times in
+// RFC 3339 format, with sub-second precision so short runs stay ordered.
+func withRunMeta(runId string, started, finished time.Time, results interface{}) runMeta {
+	return runMeta{
+		RunId:      runId,
+		StartedAt:  started.Format(time.RFC3339Nano),
+		FinishedAt: finished.Format(time.RFC3339Nano),
+		Results:    results,
+	}
+}
@@ -0,0 +1,56 @@
+package main
+
+import (
+	"encoding/json"
+	"regexp"
+	"testing"
+	"time"
+)
+
+// Test case for wrapping results with a run id and timestamps
+func TestWithRunMeta(t *testing.T) {
+	started := time.Now()
+	output, err := processInput([]byte(`{"xpaths": ["//title"], "urls": {"http://example.com": {"content": "<html><head><title>Test Page</title></head></html>"}}}`))
+	if err != nil {
+		t.Fatalf("processInput returned an unexpected error: %v", err)
+	}
+	finished := time.Now()
+	runId, err := newRunId()
+	if err != nil {
+		t.Fatalf("newRunId returned an unexpected error: %v", err)
+	}
+
+	wrappedJson, err := marshalOutput(withRunMeta(runId, started, finished, output), false)
+	if err != nil {
+		t.Fatalf("marshalOutput returned an unexpected error: %v", err)
+	}
+
+	var actual struct {
+		RunId      string     `json:"run_id"`
+		StartedAt  string     `json:"started_at"`
+		FinishedAt string     `json:"finished_at"`
+		Results    OutputJson `json:"results"`
+	}
+	if err := json.Unmarshal(wrappedJson, &actual); err != nil {
+		t.Fatalf("Failed to parse run meta output: %v", err)
+	}
+
+	uuidV4 := regexp.MustCompile(`^[0-9a-f]{8}-[0-9a-f]{4}-4[0-9a-f]{3}-[89ab][0-9a-f]{3}-[0-9a-f]{12}$`)
+	if !uuidV4.MatchString(actual.RunId) {
+		t.Errorf("Expected a version 4 UUID run id, got %q", actual.RunId)
+	}
+	startedAt, err := time.Parse(time.RFC3339Nano, actual.StartedAt)
+	if err != nil {
+		t.Fatalf("Failed to parse started_at %q: %v", actual.StartedAt, err)
+	}
+	finishedAt, err := time.Parse(time.RFC3339Nano, actual.FinishedAt)
+	if err != nil {
+		t.Fatalf("Failed to parse finished_at %q: %v", actual.FinishedAt, err)
+	}
+	if !finishedAt.After(startedAt) {
+		t.Errorf("Expected finished_at %s after started_at %s", actual.FinishedAt, actual.StartedAt)
+	}
+	if actual.Results["//title"]["http://example.com"] != "Test Page" {
+		t.Errorf("Expected results to be embedded unchanged, got %v", actual.Results)
+	}
+}
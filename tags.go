@@ -0,0 +1,41 @@
+package main
+
+// Tag filter modes for --tag-mode.
+const (
+	tagModeAny = "any" // A selector runs if it has any of the --tag values
+	tagModeAll = "all" // A selector runs only if it has every --tag value
+)
+
+// filterByTags returns the selectors that pass the --tag filter in opts. With no
+// tags requested every selector passes. Otherwise untagged selectors are dropped
+// unless opts.IncludeUntagged is set.
+func filterByTags(selectors []Selector, opts Options) []Selector {
+	if len(opts.Tags) == 0 {
+		return selectors
+	}
+
+	filtered := make([]Selector, 0, len(selectors))
+	for _, sel := range selectors {
+		if len(sel.Tags) == 0 {
+			if opts.IncludeUntagged {
+				filtered = append(filtered, sel)
+			}
+			continue
+		}
+
+		has := make(map[string]bool, len(sel.Tags))
+		for _, tag := range sel.Tags {
+			has[tag] = true
+		}
+		matched := 0
+		for _, tag := range opts.Tags {
+			if has[tag] {
+				matched++
+			}
+		}
+		if (opts.TagMode == tagModeAll && matched == len(opts.Tags)) || (opts.TagMode != tagModeAll && matched > 0) {
+			filtered = append(filtered, sel)
+		}
+	}
+	return filtered
+}
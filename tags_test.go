@@ -0,0 +1,53 @@
+package main
+
+import (
+	"context"
+	"reflect"
+	"sort"
+	"testing"
+)
+
+// Test case for running only the selectors carrying a tag
+func TestProcessInput_TagFilter(t *testing.T) {
+	inputJsonBytes := []byte(`{
+		"xpaths": [
+			{"xpath": "//title", "tags": ["catalog"]},
+			{"xpath": "//button", "tags": ["checkout"]},
+			{"xpath": "//form", "tags": ["checkout", "catalog"]},
+			"/html/body/p"
+		],
+		"urls": {
+			"http://example.com": {
+				"content": "<html><head><title>Shop</title></head><body><p>Hi</p><form>F</form><button>Buy</button></body></html>"
+			}
+		}
+	}`)
+
+	selectorsRun := func(opts Options) []string {
+		output, err := processInputWithOptions(context.Background(), inputJsonBytes, opts)
+		if err != nil {
+			t.Fatalf("processInputWithOptions returned an unexpected error: %v", err)
+		}
+		var keys []string
+		for xpathStr := range output {
+			keys = append(keys, xpathStr)
+		}
+		sort.Strings(keys)
+		return keys
+	}
+
+	tests := []struct {
+		name string
+		opts Options
+		want []string
+	}{
+		{"any", Options{Tags: []string{"checkout"}, TagMode: tagModeAny}, []string{"//button", "//form"}},
+		{"all", Options{Tags: []string{"checkout", "catalog"}, TagMode: tagModeAll}, []string{"//form"}},
+		{"include untagged", Options{Tags: []string{"catalog"}, IncludeUntagged: true}, []string{"//form", "//title", "/html/body/p"}},
+	}
+	for _, tt := range tests {
+		if got := selectorsRun(tt.opts); !reflect.DeepEqual(got, tt.want) {
+			t.Errorf("%s: expected selectors %v to run, got %v", tt.name, tt.want, got)
+		}
+	}
+}
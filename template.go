@@ -0,0 +1,45 @@
+package main
+
+import (
+	"fmt"
+	"regexp"
+)
+
+// templateField matches a {field} placeholder in a --template string.
+var templateField = regexp.MustCompile(`\{(\w+)\}`)
+
+// templateFields maps each --template field to the part of a record it renders.
+// "actual" is the extracted value, empty for a failing pair; there are no
+// expected values to render.
+var templateFields = map[string]func(record) string{
+	"status":   recordStatus,
+	"url":      func(r record) string { return r.Url },
+	"selector": func(r record) string { return r.Selector },
+	"actual":   func(r record) string { return r.Value },
+	"error":    func(r record) string { return r.Error },
+}
+
+// recordStatus renders whether the pair produced a value, as "ok" or "fail".
+func recordStatus(r record) string {
+	if r.Matched {
+		return "ok"
+	}
+	return "fail"
+}
+
+// validateTemplate rejects --template strings naming unknown fields.
+func validateTemplate(tmpl string) error {
+	for _, m := range templateField.FindAllStringSubmatch(tmpl, -1) {
+		if templateFields[m[1]] == nil {
+			return fmt.Errorf("unknown template field {%s}: expected {status}, {url}, {selector}, {actual} or {error}", m[1])
+		}
+	}
+	return nil
+}
+
+// renderTemplate substitutes a record's fields into a validated template.
+func renderTemplate(tmpl string, r record) string {
+	return templateField.ReplaceAllStringFunc(tmpl, func(placeholder string) string {
+		return templateFields[placeholder[1:len(placeholder)-1]](r)
+	})
+}
@@ -0,0 +1,29 @@
+package main
+
+import "testing"
+
+// Test case for rendering results through a --template string
+func TestRenderTemplate(t *testing.T) {
+	tmpl := "{status} {url} {selector} -> {actual}{error}"
+	if err := validateTemplate(tmpl); err != nil {
+		t.Fatalf("validateTemplate returned an unexpected error: %v", err)
+	}
+
+	r := record{Selector: "//title", Url: "http://example.com", Value: "Test Page", Matched: true}
+	expected := "ok http://example.com //title -> Test Page"
+	if actual := renderTemplate(tmpl, r); actual != expected {
+		t.Errorf("Unexpected rendered line.\nExpected: %q\nGot: %q", expected, actual)
+	}
+
+	// A failing pair renders its status and error
+	r = record{Selector: "//title", Url: "http://broken.com", Error: "parsing: EOF"}
+	expected = "fail http://broken.com //title -> parsing: EOF"
+	if actual := renderTemplate(tmpl, r); actual != expected {
+		t.Errorf("Unexpected rendered line for a failing pair.\nExpected: %q\nGot: %q", expected, actual)
+	}
+
+	// Fields this tool has no data for are rejected up front
+	if err := validateTemplate("{expected} {url}"); err == nil {
+		t.Errorf("Expected an error for the unknown field {expected}, got nil")
+	}
+}
@@ -0,0 +1,95 @@
+package main
+
+import (
+	"fmt"
+	"os"
+	"regexp"
+	"strings"
+)
+
+// Values for UrlData.ContentType.
+const (
+	contentTypeHTML = "html" // The default: parse as markup and evaluate XPaths
+	contentTypeText = "text" // Plain text: each selector is a regex matched per line
+)
+
+// compileLineRegexes compiles every selector's XPath string as a line regex for
+// plain-text URLs, warning about and leaving out those that are not valid regexes
+// or that use settings text URLs do not support. It also returns why each
+// left-out selector was, by XPath.
+func compileLineRegexes(selectors []Selector) (map[string]*regexp.Regexp, map[string]string) {
+	lineRegexes := make(map[string]*regexp.Regexp, len(selectors))
+	lineErrors := make(map[string]string)
+	for _, sel := range selectors {
+		if unsupported := textUnsupportedSettings(sel); len(unsupported) > 0 {
+			fmt.Fprintf(os.Stderr, "Warning: Selector %s uses %s, which text URLs do not support. Skipping it for text URLs.\n", sel.label(), strings.Join(unsupported, ", "))
+			lineErrors[sel.Xpath] = fmt.Sprintf("%s not supported for text URLs", strings.Join(unsupported, ", "))
+			continue
+		}
+		re, err := regexp.Compile(sel.Xpath)
+		if err != nil {
+			fmt.Fprintf(os.Stderr, "Warning: Selector %s is not a valid line regex: %v. Skipping it for text URLs.\n", sel.label(), err)
+			lineErrors[sel.Xpath] = fmt.Sprintf("not a valid line regex: %v", err)
+			continue
+		}
+		lineRegexes[sel.Xpath] = re
+	}
+	return lineRegexes, lineErrors
+}
+
+// textUnsupportedSettings returns the names of the selector's settings that only
+// apply to markup. A text URL reports the matching line as it is.
+func textUnsupportedSettings(sel Selector) []string {
+	var unsupported []string
+	for _, setting := range []struct {
+		name string
+		set  bool
+	}{
+		{"url_policy", sel.UrlPolicy != "" && sel.UrlPolicy != urlPolicyAll},
+		{"transform", len(sel.Transform) > 0},
+		{"extract_regex", sel.ExtractRegex != ""},
+		{"fallbacks", len(sel.Fallbacks) > 0},
+		{"attributes", len(sel.Attributes) > 0},
+		{"when", sel.When != ""},
+		{"concat", len(sel.Concat) > 0},
+		{"expect_type", sel.ExpectType != ""},
+		{"expect_distinct_count", sel.ExpectDistinctCount != 0},
+		{"resolve_urls", sel.ResolveUrls},
+	} {
+		if setting.set {
+			unsupported = append(unsupported, setting.name)
+		}
+	}
+	return unsupported
+}
+
+// hasTreeContent reports whether any URL in the input is parsed as markup.
+func hasTreeContent(input InputJson) bool {
+	for _, urlData := range input.Urls {
+		if urlData.ContentType != contentTypeText {
+			return true
+		}
+	}
+	return false
+}
+
+// hasTextContent reports whether any URL in the input is plain text.
+func hasTextContent(input InputJson) bool {
+	for _, urlData := range input.Urls {
+		if urlData.ContentType == contentTypeText {
+			return true
+		}
+	}
+	return false
+}
+
+// firstMatchingLine returns the first line of content that re matches.
+func firstMatchingLine(re *regexp.Regexp, content string) (string, bool) {
+	for _, line := range strings.Split(content, "\n") {
+		line = strings.TrimSuffix(line, "\r")
+		if re.MatchString(line) {
+			return line, true
+		}
+	}
+	return "", false
+}
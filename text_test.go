@@ -0,0 +1,140 @@
+package main
+
+import (
+	"context"
+	"encoding/json"
+	"reflect"
+	"strings"
+	"testing"
+)
+
+// Test case for line-regex selectors over plain-text content
+func TestProcessInput_TextContent(t *testing.T) {
+	inputJsonBytes := []byte(`{
+		"xpaths": ["^ERROR \\d+", "//title"],
+		"urls": {
+			"http://logs.example.com/app.log": {
+				"content_type": "text",
+				"content": "INFO starting\nERROR 42 disk full\r\nERROR 43 retry\n"
+			},
+			"http://example.com": {
+				"content": "<html><head><title>Test Page</title></head></html>"
+			}
+		}
+	}`)
+
+	expectedOutput := OutputJson{
+		// The first matching line is reported for text content
+		"^ERROR \\d+": {
+			"http://logs.example.com/app.log": "ERROR 42 disk full",
+		},
+		// HTML content is still evaluated as XPath; the regex does not match the log
+		"//title": {
+			"http://example.com": "Test Page",
+		},
+	}
+
+	actualOutput, err := processInput(inputJsonBytes)
+	if err != nil {
+		t.Fatalf("processInput returned an unexpected error: %v", err)
+	}
+
+	if !reflect.DeepEqual(expectedOutput, actualOutput) {
+		expectedJson, _ := json.MarshalIndent(expectedOutput, "", "  ")
+		actualJson, _ := json.MarshalIndent(actualOutput, "", "  ")
+		t.Errorf("Unexpected output for text content.\nExpected:\n%s\nGot:\n%s", string(expectedJson), string(actualJson))
+	}
+}
+
+// Test case for selectors whose settings do not apply to text URLs
+func TestProcessInput_TextContentUnsupportedSettings(t *testing.T) {
+	inputJsonBytes := []byte(`{
+		"xpaths": [
+			{"xpath": "^ERROR", "transform": ["lower"]},
+			"^WARN"
+		],
+		"urls": {
+			"http://logs.example.com/app.log": {
+				"content_type": "text",
+				"content": "ERROR disk full\nWARN retrying\n"
+			}
+		}
+	}`)
+
+	var actualOutput OutputJson
+	var err error
+	stderr := captureStderr(t, func() {
+		actualOutput, err = processInput(inputJsonBytes)
+	})
+	if err != nil {
+		t.Fatalf("processInput returned an unexpected error: %v", err)
+	}
+
+	// The transform would be silently ignored, so the selector is skipped instead
+	expectedOutput := OutputJson{
+		"^WARN": {
+			"http://logs.example.com/app.log": "WARN retrying",
+		},
+		"^ERROR": {},
+	}
+	if !reflect.DeepEqual(expectedOutput, actualOutput) {
+		expectedJson, _ := json.MarshalIndent(expectedOutput, "", "  ")
+		actualJson, _ := json.MarshalIndent(actualOutput, "", "  ")
+		t.Errorf("Unexpected output for unsupported text settings.\nExpected:\n%s\nGot:\n%s", string(expectedJson), string(actualJson))
+	}
+	if !strings.Contains(stderr, "'^ERROR' uses transform, which text URLs do not support") {
+		t.Errorf("Expected a warning naming the unsupported setting, got:\n%s", stderr)
+	}
+}
+
+// Test case for tree-based modes leaving text URLs out quietly
+func TestParsePages_SkipsTextContent(t *testing.T) {
+	input := InputJson{
+		Urls: map[string]UrlData{
+			"http://logs.example.com/app.log": {ContentType: contentTypeText, Content: "ERROR <unclosed\n"},
+			"http://example.com":              {Content: "<html><body><p>Hi</p></body></html>"},
+		},
+	}
+
+	var pages []parsedPage
+	stderr := captureStderr(t, func() {
+		pages = parsePages(context.Background(), input, Options{})
+	})
+	if len(pages) != 1 || pages[0].url != "http://example.com" {
+		t.Errorf("Expected only the HTML page to be parsed, got %v", pages)
+	}
+	if stderr != "" {
+		t.Errorf("Expected no warnings for a text URL, got:\n%s", stderr)
+	}
+}
+
+// Test case for line-regex selectors that are not XPaths, in an input with only text URLs
+func TestProcessInput_TextOnlyRegexSelectors(t *testing.T) {
+	input := InputJson{
+		Xpaths: []Selector{{Xpath: `^ERROR \d+`}, {Xpath: "^FATAL"}},
+		Urls: map[string]UrlData{
+			"http://logs.example.com/app.log": {ContentType: contentTypeText, Content: "INFO starting\nERROR 42 disk full\n"},
+		},
+	}
+
+	var run runResult
+	var err error
+	stderr := captureStderr(t, func() {
+		run, err = runInput(context.Background(), input, Options{})
+	})
+	if err != nil {
+		t.Fatalf("runInput returned an unexpected error: %v", err)
+	}
+
+	// No XPath is evaluated, so failing to compile one is not reported
+	if strings.Contains(stderr, "Failed to compile selector") {
+		t.Errorf("Expected no XPath compile warning for text-only input, got:\n%s", stderr)
+	}
+	expectedRecords := []record{
+		{Selector: `^ERROR \d+`, Url: "http://logs.example.com/app.log", Value: "ERROR 42 disk full", Matched: true},
+		{Selector: "^FATAL", Url: "http://logs.example.com/app.log"},
+	}
+	if actualRecords := toRecords(run); !reflect.DeepEqual(expectedRecords, actualRecords) {
+		t.Errorf("Unexpected records.\nExpected: %+v\nGot: %+v", expectedRecords, actualRecords)
+	}
+}
@@ -0,0 +1,85 @@
+package main
+
+import (
+	"fmt"
+	"io"
+	"sort"
+	"time"
+)
+
+// timingReport collects a hierarchical breakdown of processing time: the whole run,
+// each URL (parse plus evaluation), and each selector within a URL.
+// A nil *timingReport records nothing and never reads the clock, so timing costs
+// next to nothing when the report is disabled.
+type timingReport struct {
+	started time.Time
+	urls    []*urlTiming
+}
+
+type urlTiming struct {
+	url       string
+	parse     time.Duration
+	selectors []selectorTiming
+}
+
+type selectorTiming struct {
+	xpath string
+	eval  time.Duration
+}
+
+func newTimingReport() *timingReport {
+	return &timingReport{started: time.Now()}
+}
+
+// start returns the current time, or the zero time if timing is disabled.
+func (r *timingReport) start() time.Time {
+	if r == nil {
+		return time.Time{}
+	}
+	return time.Now()
+}
+
+// addURL records a URL whose parse began at parseStart and returns the entry to
+// attach selector timings to.
+func (r *timingReport) addURL(url string, parseStart time.Time) *urlTiming {
+	if r == nil {
+		return nil
+	}
+	u := &urlTiming{url: url, parse: time.Since(parseStart)}
+	r.urls = append(r.urls, u)
+	return u
+}
+
+// addSelector records an XPath evaluation that began at evalStart.
+func (u *urlTiming) addSelector(xpathStr string, evalStart time.Time) {
+	if u == nil {
+		return
+	}
+	u.selectors = append(u.selectors, selectorTiming{xpath: xpathStr, eval: time.Since(evalStart)})
+}
+
+func (u *urlTiming) total() time.Duration {
+	total := u.parse
+	for _, s := range u.selectors {
+		total += s.eval
+	}
+	return total
+}
+
+// write prints the report as an indented tree, slowest entries first.
+func (r *timingReport) write(w io.Writer) {
+	if r == nil {
+		return
+	}
+	fmt.Fprintf(w, "total %v\n", time.Since(r.started))
+
+	sort.SliceStable(r.urls, func(i, j int) bool { return r.urls[i].total() > r.urls[j].total() })
+	for _, u := range r.urls {
+		fmt.Fprintf(w, "  %s %v (parse %v)\n", u.url, u.total(), u.parse)
+
+		sort.SliceStable(u.selectors, func(i, j int) bool { return u.selectors[i].eval > u.selectors[j].eval })
+		for _, s := range u.selectors {
+			fmt.Fprintf(w, "    %s %v\n", s.xpath, s.eval)
+		}
+	}
+}
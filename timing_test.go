@@ -0,0 +1,55 @@
+package main
+
+import (
+	"bytes"
+	"context"
+	"strings"
+	"testing"
+)
+
+// Test case for the hierarchical timing report
+func TestProcessInput_TimingReport(t *testing.T) {
+	inputJsonBytes := []byte(`{
+		"xpaths": ["/html/body/p", "//title"],
+		"urls": {
+			"http://example.com": {
+				"content": "<html><head><title>Test Page</title></head><body><p>Hello</p></body></html>"
+			},
+			"http://other.com": {
+				"content": "<html><body><p>Other</p></body></html>"
+			}
+		}
+	}`)
+
+	var report bytes.Buffer
+	_, err := processInputWithOptions(context.Background(), inputJsonBytes, Options{TimingReport: &report})
+	if err != nil {
+		t.Fatalf("processInputWithOptions returned an unexpected error: %v", err)
+	}
+
+	lines := strings.Split(strings.TrimRight(report.String(), "\n"), "\n")
+	if !strings.HasPrefix(lines[0], "total ") {
+		t.Errorf("Expected the report to start with the total, got %q", lines[0])
+	}
+
+	// Each URL appears once, indented under the total, followed by its selectors
+	countLines := func(prefix string) int {
+		n := 0
+		for _, line := range lines {
+			if strings.HasPrefix(line, prefix) {
+				n++
+			}
+		}
+		return n
+	}
+	for _, url := range []string{"http://example.com", "http://other.com"} {
+		if n := countLines("  " + url + " "); n != 1 {
+			t.Errorf("Expected one report line for %s, got %d:\n%s", url, n, report.String())
+		}
+	}
+	for _, xpathStr := range []string{"/html/body/p", "//title"} {
+		if n := countLines("    " + xpathStr + " "); n != 2 {
+			t.Errorf("Expected a report line for %s under each URL, got %d:\n%s", xpathStr, n, report.String())
+		}
+	}
+}
@@ -0,0 +1,50 @@
+package main
+
+import (
+	"fmt"
+	"strings"
+)
+
+// transformOp is one step of a selector's "transform" pipeline.
+type transformOp func(string) string
+
+// parseTransform turns a selector's transform op strings into functions, rejecting
+// unknown ops and malformed arguments.
+func parseTransform(ops []string) ([]transformOp, error) {
+	steps := make([]transformOp, 0, len(ops))
+	for _, op := range ops {
+		name, arg, hasArg := strings.Cut(op, ":")
+		if hasArg && name != "replace" {
+			return nil, fmt.Errorf("transform op %q takes no argument", name)
+		}
+
+		switch name {
+		case "upper":
+			steps = append(steps, strings.ToUpper)
+		case "lower":
+			steps = append(steps, strings.ToLower)
+		case "trim":
+			steps = append(steps, strings.TrimSpace)
+		case "reverse":
+			steps = append(steps, reverseString)
+		case "replace":
+			from, to, ok := strings.Cut(arg, ":")
+			if !ok || from == "" {
+				return nil, fmt.Errorf("transform op %q must have the form replace:<from>:<to>", op)
+			}
+			steps = append(steps, func(s string) string { return strings.ReplaceAll(s, from, to) })
+		default:
+			return nil, fmt.Errorf("unknown transform op %q", op)
+		}
+	}
+	return steps, nil
+}
+
+// reverseString reverses s rune by rune.
+func reverseString(s string) string {
+	runes := []rune(s)
+	for i, j := 0, len(runes)-1; i < j; i, j = i+1, j-1 {
+		runes[i], runes[j] = runes[j], runes[i]
+	}
+	return string(runes)
+}
@@ -0,0 +1,82 @@
+package main
+
+import (
+	"encoding/json"
+	"reflect"
+	"testing"
+)
+
+// Test case for per-selector transforms applied to extracted values
+func TestProcessInput_Transform(t *testing.T) {
+	inputJsonBytes := []byte(`{
+		"xpaths": [
+			{"xpath": "//title", "transform": ["upper"]},
+			{"xpath": "/html/body/p", "transform": ["replace:World:There", "reverse"]},
+			{"xpath": "//h1", "transform": ["shout"]}
+		],
+		"urls": {
+			"http://example.com": {
+				"content": "<html><head><title>Test Page</title></head><body><h1>Head</h1><p>Hello World</p></body></html>"
+			}
+		}
+	}`)
+
+	expectedOutput := OutputJson{
+		"//title": {
+			"http://example.com": "TEST PAGE",
+		},
+		// Ops are applied in order
+		"/html/body/p": {
+			"http://example.com": "erehT olleH",
+		},
+		// An unknown op fails validation, so the selector is skipped
+		"//h1": {},
+	}
+
+	actualOutput, err := processInput(inputJsonBytes)
+	if err != nil {
+		t.Fatalf("processInput returned an unexpected error: %v", err)
+	}
+
+	if !reflect.DeepEqual(expectedOutput, actualOutput) {
+		expectedJson, _ := json.MarshalIndent(expectedOutput, "", "  ")
+		actualJson, _ := json.MarshalIndent(actualOutput, "", "  ")
+		t.Errorf("Unexpected output for transforms.\nExpected:\n%s\nGot:\n%s", string(expectedJson), string(actualJson))
+	}
+}
+
+// Test case for narrowing extracted values with extract_regex
+func TestProcessInput_ExtractRegex(t *testing.T) {
+	inputJsonBytes := []byte(`{
+		"xpaths": [
+			{"xpath": "//span/@style", "extract_regex": "rgb\\((\\d+),"}
+		],
+		"urls": {
+			"http://red.com": {
+				"content": "<html><body><span style=\"color: rgb(255,0,0)\">Red</span></body></html>"
+			},
+			"http://named.com": {
+				"content": "<html><body><span style=\"color: red\">Red</span></body></html>"
+			}
+		}
+	}`)
+
+	expectedOutput := OutputJson{
+		"//span/@style": {
+			// Only the first capture group is kept
+			"http://red.com": "255",
+			// "http://named.com" is omitted because the regex does not match its value
+		},
+	}
+
+	actualOutput, err := processInput(inputJsonBytes)
+	if err != nil {
+		t.Fatalf("processInput returned an unexpected error: %v", err)
+	}
+
+	if !reflect.DeepEqual(expectedOutput, actualOutput) {
+		expectedJson, _ := json.MarshalIndent(expectedOutput, "", "  ")
+		actualJson, _ := json.MarshalIndent(actualOutput, "", "  ")
+		t.Errorf("Unexpected output for extract_regex.\nExpected:\n%s\nGot:\n%s", string(expectedJson), string(actualJson))
+	}
+}
@@ -0,0 +1,44 @@
+package main
+
+import (
+	"fmt"
+	"net/url"
+	"regexp"
+	"sort"
+	"strings"
+	"time"
+)
+
+var (
+	emailValue   = regexp.MustCompile(`^[^@\s]+@[^@\s]+\.[^@\s]+$`)
+	uuidValue    = regexp.MustCompile(`^[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}$`)
+	integerValue = regexp.MustCompile(`^[+-]?[0-9]+$`)
+)
+
+// valueTypes are the validators available to a selector's expect_type, by name.
+var valueTypes = map[string]func(string) bool{
+	"email":   emailValue.MatchString,
+	"uuid":    uuidValue.MatchString,
+	"integer": integerValue.MatchString,
+	"url": func(value string) bool {
+		u, err := url.Parse(value)
+		return err == nil && u.Scheme != "" && u.Host != ""
+	},
+	"iso_date": func(value string) bool {
+		_, err := time.Parse("2006-01-02", value)
+		return err == nil
+	},
+}
+
+// valueTypeValidator returns the validator for an expect_type name.
+func valueTypeValidator(name string) (func(string) bool, error) {
+	if validate, ok := valueTypes[name]; ok {
+		return validate, nil
+	}
+	names := make([]string, 0, len(valueTypes))
+	for known := range valueTypes {
+		names = append(names, known)
+	}
+	sort.Strings(names)
+	return nil, fmt.Errorf("invalid expect_type %q: expected one of %s", name, strings.Join(names, ", "))
+}